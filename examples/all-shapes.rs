@@ -3,8 +3,7 @@ use raytracer::{
     camera::Camera,
     material::Material,
     shapes::{
-        /* a_box, cylinder::Cylinder,*/
-        sphere::Sphere, Shapes,
+        a_box, cylinder::Cylinder, sphere::Sphere, Shapes,
     },
 };
 use std::io;
@@ -31,11 +30,11 @@ fn main() -> io::Result<()> {
         100.0,
         material_ground,
     )));
-    // world.push(Shapes::Box(a_box::Box {
-    //     center: DVec3::new(0.0, 0.0, -1.0),
-    //     size: DVec3::splat(0.2),
-    //     material: material_center,
-    // }));
+    world.push(Shapes::Box(a_box::Box::new(
+        DVec3::new(0.0, 0.0, -1.0),
+        DVec3::splat(0.2),
+        material_center,
+    )));
     world.push(Shapes::Sphere(Sphere::new(
         DVec3::new(-1.0, 0.0, -1.0),
         0.5,
@@ -46,12 +45,12 @@ fn main() -> io::Result<()> {
         -0.4,
         material_left,
     )));
-    // world.push(Shapes::Cylinder(Cylinder {
-    //     start: DVec3::splat(-1.),
-    //     end: DVec3::splat(-2.),
-    //     radius: 0.5,
-    //     material: material_right,
-    // }));
+    world.push(Shapes::Cylinder(Cylinder::new(
+        DVec3::new(1.0, -0.5, -1.0),
+        DVec3::new(1.0, 0.5, -1.0),
+        0.4,
+        material_right,
+    )));
 
     let camera = Camera::init()
         .image_width(800)
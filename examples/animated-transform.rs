@@ -0,0 +1,97 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        animated_transform::{AnimatedTransform, Keyframe},
+        plane::Plane,
+        quad_box::QuadBox,
+        Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::new(0.2, 0.3, 0.1),
+        odd: DVec3::splat(0.9),
+        scale: 0.32,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    // a box that spins half a turn while sliding sideways
+    world.push(Shapes::AnimatedTransform(
+        AnimatedTransform::new(
+            vec![
+                Keyframe::new(0.).with_translation(
+                    DVec3::new(-0.5, 1., 0.),
+                ),
+                Keyframe::new(1.)
+                    .with_translation(DVec3::new(
+                        0.5, 1., 0.,
+                    ))
+                    .with_rotation(DVec3::Y, 90.),
+            ],
+            Shapes::QuadBox(QuadBox::new(
+                DVec3::splat(-0.5),
+                DVec3::splat(0.5),
+                Material::Lambertian {
+                    albedo: DVec3::new(0.8, 0.3, 0.2)
+                        .into(),
+                },
+            )),
+        ),
+    ));
+
+    // a box that grows without moving
+    world.push(Shapes::AnimatedTransform(
+        AnimatedTransform::new(
+            vec![
+                Keyframe::new(0.)
+                    .with_translation(DVec3::new(
+                        2.5, 0.5, 0.,
+                    ))
+                    .with_scale(DVec3::splat(0.5)),
+                Keyframe::new(1.)
+                    .with_translation(DVec3::new(
+                        2.5, 0.5, 0.,
+                    ))
+                    .with_scale(DVec3::splat(1.)),
+            ],
+            Shapes::QuadBox(QuadBox::new(
+                DVec3::splat(-0.5),
+                DVec3::splat(0.5),
+                Material::Metal {
+                    albedo: DVec3::new(0.8, 0.8, 0.9),
+                    fuzz: 0.1,
+                },
+            )),
+        ),
+    ));
+
+    let camera = Camera::init()
+        .image_width(400)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(1., 3., 8.))
+        .look_at(DVec3::new(1., 0.5, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -2,10 +2,7 @@ use glam::DVec3;
 use raytracer::{
     camera::Camera,
     material::Material,
-    shapes::{
-        /* a_box, rounded_box::RoundedBox,*/
-        sphere::Sphere, Shapes,
-    },
+    shapes::{a_box, sphere::Sphere, Shapes},
 };
 use std::io;
 
@@ -24,11 +21,24 @@ fn main() -> io::Result<()> {
         100.0,
         material_ground,
     )));
-    // world.push(Shapes::Box(a_box::Box {
-    //     center: DVec3::new(3.0, 0.0, -1.0),
-    //     size: DVec3::ONE * 9.,
-    //     material: material_center,
-    // }));
+    world.push(Shapes::Box(
+        a_box::Box::new(
+            DVec3::new(3.0, 0.0, -1.0),
+            DVec3::ONE * 9.,
+            material_center,
+        )
+        .with_rotation(DVec3::Y, 30.),
+    ));
+    world.push(Shapes::Box(
+        a_box::Box::new(
+            DVec3::new(-20.0, 10.0, 20.0),
+            DVec3::new(4., 12., 4.),
+            Material::Dielectric {
+                index_of_refraction: 1.5,
+            },
+        )
+        .with_rotation(DVec3::new(1., 0., 1.), 20.),
+    ));
 
     let camera = Camera::init()
         .image_width(600)
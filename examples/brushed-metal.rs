@@ -0,0 +1,97 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        disk::Disk, plane::Plane, sphere::Sphere, Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian {
+            albedo: DVec3::splat(0.1).into(),
+        },
+    )));
+
+    // the bottom of a pan, brushed in circles around its
+    // center so the highlight stretches out along the
+    // radius
+    world.push(Shapes::Disk(Disk::new(
+        DVec3::new(0., 0.01, 1.5),
+        DVec3::Y,
+        2.,
+        Material::aluminium(0.3).with_anisotropy(-0.9, 0.),
+    )));
+
+    // more and more stretched highlights from left to
+    // right, then tiles of steel brushed at right angles
+    // to each other
+    let metals = [
+        Material::silver(0.3).with_anisotropy(0., 0.),
+        Material::silver(0.3).with_anisotropy(0.5, 0.),
+        Material::silver(0.3).with_anisotropy(0.9, 0.),
+        Material::Conductor {
+            eta: DVec3::new(2.87, 2.95, 2.65),
+            k: DVec3::new(3.18, 2.93, 2.81),
+            roughness: 0.3,
+        }
+        .with_anisotropy(
+            0.9,
+            Texture::Checkered {
+                even: DVec3::ZERO,
+                odd: DVec3::splat(0.25),
+                scale: 0.25,
+            },
+        ),
+    ];
+    for (i, material) in metals.into_iter().enumerate() {
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(-3. + 2. * i as f64, 0.7, -1.5),
+            0.7,
+            material,
+        )));
+    }
+
+    // a lamp above and behind the camera for the spheres,
+    // and one just out of view for the disk to reflect
+    let lamps = [
+        (DVec3::new(0., 8., 8.), 1.),
+        (DVec3::new(0., 5., -6.), 0.7),
+    ];
+    for (center, radius) in lamps {
+        world.push(Shapes::Sphere(Sphere::new(
+            center,
+            radius,
+            Material::DiffuseLight(
+                DVec3::splat(30.).into(),
+            ),
+        )));
+    }
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 4., 9.))
+        .look_at(DVec3::new(0., 0.5, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(40.)
+        .background(DVec3::splat(0.05))
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{plane::Plane, sphere::Sphere, Shapes},
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::OrenNayar {
+            albedo: DVec3::splat(0.5).into(),
+            sigma: 30.,
+        },
+    )));
+
+    // clay, getting flatter from a Lambertian sphere on
+    // the left as the facets get rougher
+    let clay = DVec3::new(0.7, 0.45, 0.3);
+    for (i, sigma) in
+        [0., 20., 40., 60.].into_iter().enumerate()
+    {
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(-3. + 2. * i as f64, 0.7, 1.),
+            0.7,
+            Material::OrenNayar {
+                albedo: clay.into(),
+                sigma,
+            },
+        )));
+    }
+
+    // velvet, from a tight sheen to a soft one, with a
+    // checkered one at the end
+    let velvets = [
+        (DVec3::new(0.3, 0.02, 0.05).into(), 0.3),
+        (DVec3::new(0.03, 0.05, 0.3).into(), 0.6),
+        (DVec3::new(0.05, 0.2, 0.05).into(), 1.),
+        (
+            Texture::Checkered {
+                even: DVec3::new(0.4, 0.3, 0.05),
+                odd: DVec3::splat(0.05),
+                scale: 0.2,
+            },
+            0.5,
+        ),
+    ];
+    for (i, (albedo, roughness)) in
+        velvets.into_iter().enumerate()
+    {
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(-3. + 2. * i as f64, 0.7, -1.),
+            0.7,
+            Material::Velvet {
+                albedo,
+                sheen: DVec3::splat(0.8),
+                roughness,
+            },
+        )));
+    }
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 4., 9.))
+        .look_at(DVec3::new(0., 0.5, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
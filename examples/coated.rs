@@ -0,0 +1,99 @@
+use glam::DVec3;
+use noise::Perlin;
+use raytracer::{
+    camera::Camera,
+    material::{Coated, Material},
+    shapes::{plane::Plane, sphere::Sphere, Shapes},
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::splat(0.2),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    // car paint: a clear coat over rough red metal
+    let paint = Material::Conductor {
+        eta: DVec3::new(0.2, 1.2, 1.4),
+        k: DVec3::new(3.9, 0.6, 0.5),
+        roughness: 0.5,
+    };
+    // varnished wood: an amber coat over a grainy base
+    let wood = Material::Lambertian {
+        albedo: Texture::Turbulence(Perlin::new(3)),
+    };
+    // glazed ceramic: a thick, glossy glaze over clay
+    let clay = Material::Lambertian {
+        albedo: DVec3::new(0.8, 0.8, 0.75).into(),
+    };
+    // a satin finish from a rough coat
+    let satin = Material::Lambertian {
+        albedo: DVec3::new(0.1, 0.3, 0.15).into(),
+    };
+
+    let materials = [
+        (paint.clone(), Coated::new(paint, 1.5)),
+        (
+            wood.clone(),
+            Coated::new(wood, 1.5)
+                .with_thickness(0.05)
+                .with_absorption(DVec3::new(2., 6., 20.)),
+        ),
+        (
+            clay.clone(),
+            Coated::new(clay, 1.5)
+                .with_thickness(0.1)
+                .with_absorption(DVec3::new(8., 3., 1.)),
+        ),
+        (
+            satin.clone(),
+            Coated::new(satin, 1.5).with_roughness(0.3),
+        ),
+    ];
+
+    // the bare base in front, the coated one behind
+    for (i, (base, coated)) in
+        materials.into_iter().enumerate()
+    {
+        let x = -3. + 2. * i as f64;
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(x, 0.7, 1.5),
+            0.7,
+            base,
+        )));
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(x, 0.7, -0.5),
+            0.7,
+            coated.into(),
+        )));
+    }
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 4., 10.))
+        .look_at(DVec3::new(0., 0.6, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        cylinder::Cylinder, plane::Plane,
+        quad_box::QuadBox, sphere::Sphere, Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::new(0.2, 0.3, 0.1),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    let red = Material::Lambertian {
+        albedo: DVec3::new(0.7, 0.2, 0.1).into(),
+    };
+    let blue = Material::Lambertian {
+        albedo: DVec3::new(0.1, 0.2, 0.5).into(),
+    };
+    let glass = Material::Dielectric {
+        index_of_refraction: 1.5,
+    };
+
+    // a lens where two spheres overlap
+    let lens_center = DVec3::new(-2.5, 1., 0.);
+    world.push(Shapes::new_intersection(
+        Shapes::Sphere(Sphere::new(
+            lens_center - DVec3::new(0., 0., 1.6),
+            2.,
+            glass.clone(),
+        )),
+        Shapes::Sphere(Sphere::new(
+            lens_center + DVec3::new(0., 0., 1.6),
+            2.,
+            glass,
+        )),
+    ));
+
+    // a bowl: a hollowed out sphere with its top cut off
+    let bowl_center = DVec3::new(0., 0.8, 0.);
+    world.push(Shapes::new_difference(
+        Shapes::new_difference(
+            Shapes::Sphere(Sphere::new(
+                bowl_center,
+                0.8,
+                red.clone(),
+            )),
+            Shapes::Sphere(Sphere::new(
+                bowl_center,
+                0.7,
+                red.clone(),
+            )),
+        ),
+        Shapes::QuadBox(QuadBox::new(
+            bowl_center + DVec3::new(-1., 0.2, -1.),
+            bowl_center + DVec3::new(1., 1., 1.),
+            red,
+        )),
+    ));
+
+    // a box drilled through along two axes
+    let box_center = DVec3::new(2.5, 0.6, 0.);
+    world.push(Shapes::new_difference(
+        Shapes::QuadBox(QuadBox::new(
+            box_center - DVec3::splat(0.6),
+            box_center + DVec3::splat(0.6),
+            blue.clone(),
+        )),
+        Shapes::new_union(
+            Shapes::Cylinder(Cylinder::new(
+                box_center - DVec3::new(1., 0., 0.),
+                box_center + DVec3::new(1., 0., 0.),
+                0.35,
+                blue.clone(),
+            )),
+            Shapes::Cylinder(Cylinder::new(
+                box_center - DVec3::new(0., 0., 1.),
+                box_center + DVec3::new(0., 0., 1.),
+                0.35,
+                blue,
+            )),
+        ),
+    ));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 4., 10.))
+        .look_at(DVec3::new(0., 0.6, 0.5))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -2,11 +2,7 @@ use glam::DVec3;
 use raytracer::{
     camera::Camera,
     material::Material,
-    shapes::{
-        //cylinder::Cylinder, rounded_box::RoundedBox,
-        sphere::Sphere,
-        Shapes,
-    },
+    shapes::{cylinder::Cylinder, sphere::Sphere, Shapes},
 };
 use std::io;
 
@@ -25,17 +21,34 @@ fn main() -> io::Result<()> {
         100.0,
         material_ground,
     )));
-    // world.push(Shapes::Cylinder(Cylinder {
-    //     start: DVec3::new(0.1, 0.1, -1.),
-    //     end: DVec3::new(0.1, -0.1, -1.),
-    //     radius: 10.,
-    //     material: material_center,
-    // }));
+    world.push(Shapes::Cylinder(Cylinder::new(
+        DVec3::new(0., -0.5, -1.),
+        DVec3::new(0., 0.5, -1.),
+        0.5,
+        material_center,
+    )));
+    world.push(Shapes::Cylinder(Cylinder::new(
+        DVec3::new(-2., -0.2, -1.5),
+        DVec3::new(-1., 0.6, -0.5),
+        0.3,
+        Material::Dielectric {
+            index_of_refraction: 1.5,
+        },
+    )));
+    world.push(Shapes::Cylinder(Cylinder::new(
+        DVec3::new(1.2, -0.2, -1.),
+        DVec3::new(2.2, -0.2, -1.2),
+        0.3,
+        Material::Metal {
+            albedo: DVec3::new(0.8, 0.6, 0.2),
+            fuzz: 0.1,
+        },
+    )));
 
     let camera = Camera::init()
         .image_width(600)
         .aspect_ratio(16.0 / 9.0)
-        .look_from(DVec3::new(1., 1., 10.))
+        .look_from(DVec3::new(1., 2., 8.))
         .look_at(DVec3::NEG_Z)
         .vup(DVec3::Y)
         // .focus_dist(10.0)
@@ -0,0 +1,81 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        a_box, plane::Plane, quad::Quad, sphere::Sphere,
+        Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::new(0.8, 0.2, 0.1),
+        odd: DVec3::splat(0.9),
+        scale: 0.4,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian {
+            albedo: checker.clone(),
+        },
+    )));
+    // a backdrop to see through the glass
+    world.push(Shapes::Quad(Quad::new(
+        DVec3::new(-8., 0., -3.),
+        DVec3::new(16., 0., 0.),
+        DVec3::new(0., 6., 0.),
+        Material::Lambertian { albedo: checker },
+    )));
+
+    // each sphere is frostier than the last
+    for (i, roughness) in
+        [0., 0.1, 0.3, 0.6].into_iter().enumerate()
+    {
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(-3. + 2. * i as f64, 0.8, 0.),
+            0.8,
+            Material::RoughDielectric {
+                index_of_refraction: 1.5,
+                roughness,
+            },
+        )));
+    }
+
+    // a block of ice
+    world.push(Shapes::Box(
+        a_box::Box::new(
+            DVec3::new(0., 0.4, 2.),
+            DVec3::new(1.5, 0.4, 0.3),
+            Material::RoughDielectric {
+                index_of_refraction: 1.31,
+                roughness: 0.2,
+            },
+        )
+        .with_rotation(DVec3::Y, 10.),
+    ));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 2.5, 10.))
+        .look_at(DVec3::new(0., 0.8, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use glam::DVec3;
+use rand::Rng;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        plane::Plane, quad_box::QuadBox,
+        transform::Transform, Shapes,
+    },
+};
+use std::{io, path::Path, sync::Arc};
+
+fn main() -> io::Result<()> {
+    let mut rng = rand::thread_rng();
+
+    let mut world = vec![];
+
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian {
+            albedo: DVec3::new(0.5, 0.5, 0.5).into(),
+        },
+    )));
+
+    // one box, shared by every instance
+    let unit_box = Arc::new(Shapes::QuadBox(QuadBox::new(
+        DVec3::splat(-0.5),
+        DVec3::splat(0.5),
+        Material::Lambertian {
+            albedo: DVec3::new(0.8, 0.3, 0.2).into(),
+        },
+    )));
+
+    for _ in 0..200 {
+        let instance = Transform::new(unit_box.clone())
+            .with_scale(DVec3::new(
+                rng.gen_range(0.2..0.6),
+                rng.gen_range(0.2..1.2),
+                rng.gen_range(0.2..0.6),
+            ))
+            .with_shear(
+                (rng.gen_range(-0.3..0.3), 0.),
+                (0., 0.),
+                (0., rng.gen_range(-0.3..0.3)),
+            )
+            .with_rotation(
+                DVec3::new(
+                    rng.gen_range(-1.0..1.),
+                    1.,
+                    rng.gen_range(-1.0..1.),
+                ),
+                rng.gen_range(0.0..360.),
+            )
+            .with_translation(DVec3::new(
+                rng.gen_range(-6.0..6.),
+                0.6,
+                rng.gen_range(-6.0..6.),
+            ));
+        world.push(Shapes::Transform(instance));
+    }
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(13., 6., 9.))
+        .look_at(DVec3::ZERO)
+        .vup(DVec3::Y)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
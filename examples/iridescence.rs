@@ -0,0 +1,102 @@
+use glam::DVec3;
+use noise::Perlin;
+use raytracer::{
+    camera::Camera,
+    material::{Coated, Material, ThinFilm},
+    shapes::{
+        disk::Disk, plane::Plane, sphere::Sphere, Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    // wet asphalt
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian {
+            albedo: DVec3::splat(0.05).into(),
+        },
+    )));
+
+    // an oil slick on a puddle: a coat of water over the
+    // asphalt with a film of oil on top
+    world.push(Shapes::Disk(Disk::new(
+        DVec3::new(0., 0.01, 1.),
+        DVec3::Y,
+        3.,
+        Coated::new(
+            Material::Lambertian {
+                albedo: DVec3::splat(0.05).into(),
+            },
+            1.33,
+        )
+        .with_thin_film(
+            ThinFilm::new(300., 1.47)
+                .with_thickness_texture(
+                    Texture::Turbulence(Perlin::new(1)),
+                    0.0..500.0,
+                ),
+        )
+        .into(),
+    )));
+
+    // soap bubbles, with swirls of thicker and thinner
+    // soap
+    for (i, radius) in
+        [0.6, 0.9, 0.5].into_iter().enumerate()
+    {
+        let center =
+            DVec3::new(-2.5 + 2.5 * i as f64, 1.5, -1.);
+        world.push(Shapes::Sphere(Sphere::new(
+            center,
+            radius,
+            Material::Iridescent {
+                index_of_refraction: 1.,
+                film: ThinFilm::new(500., 1.33)
+                    .with_thickness_texture(
+                        Texture::Turbulence(Perlin::new(
+                            i as u32,
+                        )),
+                        0.0..700.0,
+                    ),
+            },
+        )));
+    }
+
+    // a beetle's shell: a filmed coat over dark green
+    world.push(Shapes::Sphere(Sphere::new(
+        DVec3::new(3., 0.6, 1.),
+        0.6,
+        Coated::new(
+            Material::Lambertian {
+                albedo: DVec3::new(0.02, 0.08, 0.03).into(),
+            },
+            1.5,
+        )
+        .with_thin_film(ThinFilm::new(350., 1.8))
+        .into(),
+    )));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 3., 9.))
+        .look_at(DVec3::new(0., 0.8, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
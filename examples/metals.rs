@@ -0,0 +1,65 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{plane::Plane, sphere::Sphere, Shapes},
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::new(0.2, 0.3, 0.1),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    // each metal from front to back gets rougher
+    let metals: [fn(f64) -> Material; 4] = [
+        Material::gold,
+        Material::copper,
+        Material::aluminium,
+        Material::silver,
+    ];
+    for (i, metal) in metals.into_iter().enumerate() {
+        for (j, roughness) in
+            [0., 0.2, 0.5].into_iter().enumerate()
+        {
+            world.push(Shapes::Sphere(Sphere::new(
+                DVec3::new(
+                    -3. + 2. * i as f64,
+                    0.7,
+                    1. - 2. * j as f64,
+                ),
+                0.7,
+                metal(roughness),
+            )));
+        }
+    }
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 5., 10.))
+        .look_at(DVec3::new(0., 0.5, -1.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::{Material, PhaseFunction},
+    shapes::{
+        constant_medium::ConstantMedium, plane::Plane,
+        sphere::Sphere, Shapes,
+    },
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian {
+            albedo: DVec3::splat(0.5).into(),
+        },
+    )));
+
+    // a lamp in a ball of fog for each phase function
+    let light =
+        Material::DiffuseLight(DVec3::splat(20.).into());
+    let phase_functions = [
+        PhaseFunction::Isotropic,
+        PhaseFunction::HenyeyGreenstein { g: 0.8 },
+        PhaseFunction::DoubleHenyeyGreenstein {
+            g_forward: 0.8,
+            g_backward: -0.5,
+            forward_weight: 0.7,
+        },
+        PhaseFunction::Rayleigh,
+    ];
+    for (i, phase_function) in
+        phase_functions.into_iter().enumerate()
+    {
+        let center =
+            DVec3::new(-4.5 + 3. * i as f64, 1.5, 0.);
+        world.push(Shapes::Sphere(Sphere::new(
+            center,
+            0.2,
+            light.clone(),
+        )));
+        world.push(Shapes::ConstantMedium(
+            ConstantMedium::new(
+                Shapes::Sphere(Sphere::new(
+                    center,
+                    1.4,
+                    light.clone(),
+                )),
+                0.6,
+                DVec3::splat(0.9).into(),
+            )
+            .with_phase_function(phase_function),
+        ));
+    }
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 2., 12.))
+        .look_at(DVec3::new(0., 1.5, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(500)
+        .max_depth(50)
+        .vfov(45.)
+        .background(DVec3::ZERO)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
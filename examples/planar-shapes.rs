@@ -0,0 +1,104 @@
+use glam::{DVec2, DVec3};
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::quad::{Quad, QuadShape},
+};
+use std::{f64::consts::PI, io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    // Materials
+    let left_red = Material::Lambertian {
+        albedo: DVec3::new(1.0, 0.2, 0.2).into(),
+    };
+    let back_green = Material::Lambertian {
+        albedo: DVec3::new(0.2, 1.0, 0.2).into(),
+    };
+    let right_blue = Material::Lambertian {
+        albedo: DVec3::new(0.2, 0.2, 1.0).into(),
+    };
+    let upper_light =
+        Material::DiffuseLight(DVec3::splat(4.).into());
+    let lower_teal = Material::Lambertian {
+        albedo: DVec3::new(0.2, 0.8, 0.8).into(),
+    };
+
+    // a five pointed star with a square hole, in the
+    // plane coordinates of the quad
+    let star = (0..10)
+        .map(|i| {
+            let angle = PI / 2. + i as f64 * PI / 5.;
+            let radius = if i % 2 == 0 { 0.5 } else { 0.2 };
+            DVec2::new(
+                0.5 + radius * angle.cos(),
+                0.5 + radius * angle.sin(),
+            )
+        })
+        .collect();
+    let hole = vec![
+        DVec2::new(0.45, 0.45),
+        DVec2::new(0.55, 0.45),
+        DVec2::new(0.55, 0.55),
+        DVec2::new(0.45, 0.55),
+    ];
+
+    world.push(Quad::triangle(
+        DVec3::new(-3., -2., 5.),
+        DVec3::new(0., 0., -4.),
+        DVec3::new(0., 4., 0.),
+        left_red,
+    ));
+    world.push(
+        Quad::new(
+            DVec3::new(-2., -2., 0.),
+            DVec3::new(4., 0., 0.),
+            DVec3::new(0., 4., 0.),
+            back_green,
+        )
+        .with_shape(QuadShape::Polygon {
+            outline: star,
+            holes: vec![hole],
+        }),
+    );
+    world.push(Quad::ellipse(
+        DVec3::new(3., 0., 3.),
+        DVec3::new(0., 0., 2.),
+        DVec3::new(0., 1.5, 0.),
+        right_blue,
+    ));
+    world.push(Quad::ellipse(
+        DVec3::new(0., 3., 3.),
+        DVec3::new(1., 0., 0.),
+        DVec3::new(0., 0., 1.),
+        upper_light,
+    ));
+    world.push(Quad::triangle(
+        DVec3::new(-2., -3., 5.),
+        DVec3::new(4., 0., 0.),
+        DVec3::new(0., 0., -4.),
+        lower_teal,
+    ));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(1.)
+        .look_from(DVec3::new(0., 0., 9.))
+        .look_at(DVec3::ZERO)
+        .vup(DVec3::Y)
+        .samples_per_pixel(500)
+        .max_depth(50)
+        .vfov(80.)
+        .background(DVec3::splat(0.2))
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use glam::DVec3;
+use noise::Perlin;
+use raytracer::{
+    camera::Camera,
+    material::{Material, Principled},
+    shapes::{plane::Plane, sphere::Sphere, Shapes},
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::splat(0.2),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    // each row sweeps one parameter from 0 to 1
+    let rows: [fn(f64) -> Principled; 4] = [
+        |t| {
+            Principled::new(DVec3::new(0.8, 0.1, 0.1))
+                .with_roughness(0.3)
+                .with_metallic(t)
+        },
+        |t| {
+            Principled::new(DVec3::new(1.0, 0.78, 0.34))
+                .with_metallic(1.)
+                .with_roughness(t)
+        },
+        |t| {
+            Principled::new(DVec3::new(0.1, 0.2, 0.6))
+                .with_roughness(0.6)
+                .with_clearcoat(t)
+        },
+        |t| {
+            Principled::new(DVec3::new(0.9, 1., 0.95))
+                .with_roughness(0.1)
+                .with_transmission(t)
+        },
+    ];
+    for (j, row) in rows.into_iter().enumerate() {
+        for i in 0..5 {
+            let t = i as f64 / 4.;
+            world.push(Shapes::Sphere(Sphere::new(
+                DVec3::new(
+                    -4. + 2. * i as f64,
+                    0.7,
+                    1. - 2. * j as f64,
+                ),
+                0.7,
+                row(t).into(),
+            )));
+        }
+    }
+
+    // velvet with a noisy roughness, and a lamp
+    world.push(Shapes::Sphere(Sphere::new(
+        DVec3::new(-6.5, 0.7, -2.),
+        0.7,
+        Principled::new(DVec3::new(0.3, 0.02, 0.1))
+            .with_sheen(DVec3::splat(0.5))
+            .with_roughness(Texture::PerlinNoise(
+                Perlin::new(7),
+                4.,
+            ))
+            .into(),
+    )));
+    world.push(Shapes::Sphere(Sphere::new(
+        DVec3::new(6.5, 0.7, -2.),
+        0.7,
+        Principled::new(DVec3::splat(0.1))
+            .with_emission(DVec3::new(4., 3., 1.5))
+            .into(),
+    )));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 10., 10.))
+        .look_at(DVec3::new(0., 0., -2.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        capsule::Capsule,
+        cone::Cone,
+        disk::{Annulus, Disk},
+        paraboloid::Paraboloid,
+        plane::Plane,
+        torus::Torus,
+        Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::new(0.2, 0.3, 0.1),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    let red = Material::Lambertian {
+        albedo: DVec3::new(0.7, 0.2, 0.1).into(),
+    };
+    let blue = Material::Lambertian {
+        albedo: DVec3::new(0.1, 0.2, 0.5).into(),
+    };
+    let gold = Material::Metal {
+        albedo: DVec3::new(0.8, 0.6, 0.2),
+        fuzz: 0.05,
+    };
+    let glass = Material::Dielectric {
+        index_of_refraction: 1.5,
+    };
+
+    world.push(Shapes::Torus(Torus::new(
+        DVec3::new(-3., 0.9, 0.),
+        DVec3::new(0., 1., 1.),
+        0.7,
+        0.25,
+        gold.clone(),
+    )));
+    world.push(Shapes::Cone(Cone::new(
+        DVec3::new(-1., 0., 0.),
+        DVec3::new(-1., 1.5, 0.),
+        0.6,
+        0.,
+        red.clone(),
+    )));
+    world.push(Shapes::Cone(Cone::new(
+        DVec3::new(-1., 0., 2.),
+        DVec3::new(-1., 0.8, 2.),
+        0.6,
+        0.3,
+        blue.clone(),
+    )));
+    world.push(Shapes::Capsule(Capsule::new(
+        DVec3::new(0.8, 0.4, -0.5),
+        DVec3::new(1.4, 1.2, 0.5),
+        0.4,
+        glass,
+    )));
+    world.push(Shapes::Paraboloid(Paraboloid::new(
+        DVec3::new(3., 0.2, 0.),
+        DVec3::new(-0.3, 1., 0.3),
+        1.2,
+        0.7,
+        gold,
+    )));
+    world.push(Shapes::Disk(Disk::new(
+        DVec3::new(1., 0.01, 2.5),
+        DVec3::Y,
+        0.6,
+        red,
+    )));
+    world.push(Shapes::Annulus(Annulus::new(
+        DVec3::new(3., 0.8, 2.5),
+        DVec3::new(0., 0.3, 1.),
+        0.3,
+        0.7,
+        blue,
+    )));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 4., 10.))
+        .look_at(DVec3::new(0., 0.6, 0.5))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -2,7 +2,9 @@ use glam::DVec3;
 use itertools::Itertools;
 use rand::prelude::*;
 use raytracer::{
-    camera::Camera, material::Material,
+    bvh::Bvh,
+    camera::Camera,
+    material::{Dispersion, Material},
     shapes::sphere::Sphere,
 };
 use std::io;
@@ -58,8 +60,8 @@ fn main() -> io::Result<()> {
                 Material::Metal { albedo, fuzz }
             } else {
                 // glass
-                Material::Dielectric {
-                    index_of_refraction: 1.5,
+                Material::DispersiveDielectric {
+                    dispersion: Dispersion::bk7(),
                 }
             };
 
@@ -70,8 +72,8 @@ fn main() -> io::Result<()> {
     world.push(Sphere::new(
         DVec3::new(0., 1., 0.),
         1.0,
-        Material::Dielectric {
-            index_of_refraction: 1.5,
+        Material::DispersiveDielectric {
+            dispersion: Dispersion::dense_flint(),
         },
     ));
 
@@ -103,11 +105,12 @@ fn main() -> io::Result<()> {
         .samples_per_pixel(500)
         .max_depth(50)
         .vfov(20.)
+        .spectral(true)
         .build();
 
     camera.render_to_disk(
         "raytracing-in-one-weekend-final-scene",
-        world,
+        Bvh::new(world),
     )?;
 
     Ok(())
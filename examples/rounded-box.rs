@@ -3,8 +3,7 @@ use raytracer::{
     camera::Camera,
     material::Material,
     shapes::{
-        /*rounded_box::RoundedBox,*/ sphere::Sphere,
-        Shapes,
+        rounded_box::RoundedBox, sphere::Sphere, Shapes,
     },
 };
 use std::io;
@@ -24,12 +23,26 @@ fn main() -> io::Result<()> {
         100.0,
         material_ground,
     )));
-    // world.push(Shapes::RoundedBox(RoundedBox {
-    //     center: DVec3::new(0.0, 0.0, -1.0),
-    //     radius: 0.2,
-    //     size: DVec3::ONE * 9.,
-    //     material: material_center,
-    // }));
+    world.push(Shapes::RoundedBox(
+        RoundedBox::new(
+            DVec3::new(0.0, 0.0, -1.0),
+            DVec3::ONE * 9.,
+            2.,
+            material_center,
+        )
+        .with_rotation(DVec3::Y, 30.),
+    ));
+    world.push(Shapes::RoundedBox(
+        RoundedBox::new(
+            DVec3::new(-25.0, 10.0, 25.0),
+            DVec3::new(3., 10., 3.),
+            2.,
+            Material::Dielectric {
+                index_of_refraction: 1.5,
+            },
+        )
+        .with_rotation(DVec3::new(1., 0., 1.), 20.),
+    ));
 
     let camera = Camera::init()
         .image_width(600)
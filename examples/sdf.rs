@@ -0,0 +1,158 @@
+use glam::DVec3;
+use raytracer::{
+    aabb::Aabb,
+    camera::Camera,
+    material::Material,
+    shapes::{
+        plane::Plane,
+        sdf::{Distance, Sdf},
+        Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+/// Distance estimate for the power 8 Mandelbulb
+fn mandelbulb(p: DVec3) -> f64 {
+    let mut z = p;
+    let mut dr = 1.;
+    let mut r = 0.;
+    for _ in 0..10 {
+        r = z.length();
+        if r > 2. {
+            break;
+        }
+        let theta = (z.z / r).acos() * 8.;
+        let phi = z.y.atan2(z.x) * 8.;
+        dr = r.powi(7) * 8. * dr + 1.;
+        z = r.powi(8)
+            * DVec3::new(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            )
+            + p;
+    }
+    0.5 * r.ln() * r / dr
+}
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::new(0.2, 0.3, 0.1),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    let red = Material::Lambertian {
+        albedo: DVec3::new(0.7, 0.2, 0.1).into(),
+    };
+    let blue = Material::Lambertian {
+        albedo: DVec3::new(0.1, 0.2, 0.5).into(),
+    };
+    let gold = Material::Metal {
+        albedo: DVec3::new(0.8, 0.6, 0.2),
+        fuzz: 0.05,
+    };
+    let glass = Material::Dielectric {
+        index_of_refraction: 1.5,
+    };
+
+    // two spheres melted together
+    let blob = Distance::Sphere { radius: 0.6 }
+        .smooth_union(
+            Distance::Sphere { radius: 0.4 }
+                .translate(DVec3::new(0.5, 0.6, 0.)),
+            0.3,
+        )
+        .translate(DVec3::new(-3., 0.6, 0.));
+    world.push(Shapes::Sdf(
+        Sdf::new(blob, red).with_bounds(Aabb::new(
+            DVec3::new(-3.7, -0.1, -0.7),
+            DVec3::new(-2., 1.7, 0.7),
+        )),
+    ));
+
+    // a twisted column with rounded edges
+    let column = Distance::Cuboid {
+        size: DVec3::new(0.3, 1., 0.3),
+    }
+    .round(0.05)
+    .twist(1.2)
+    .translate(DVec3::new(-1., 1.05, 0.));
+    world.push(Shapes::Sdf(
+        Sdf::new(column, blue.clone())
+            .with_bounds(Aabb::new(
+                DVec3::new(-1.6, 0., -0.6),
+                DVec3::new(-0.4, 2.2, 0.6),
+            ))
+            .with_step_scale(0.6),
+    ));
+
+    // a glass cube with a sphere carved out of it
+    let carved = Distance::Cuboid {
+        size: DVec3::splat(0.5),
+    }
+    .intersect(Distance::Sphere { radius: 0.7 })
+    .subtract(
+        Distance::Sphere { radius: 0.6 }
+            .translate(DVec3::new(0.3, 0.3, 0.3)),
+    )
+    .translate(DVec3::new(1., 0.5, 0.));
+    world.push(Shapes::Sdf(
+        Sdf::new(carved, glass).with_bounds(Aabb::new(
+            DVec3::new(0.4, -0.1, -0.6),
+            DVec3::new(1.6, 1.1, 0.6),
+        )),
+    ));
+
+    // a Mandelbulb, scaled down with a custom function
+    let scale = 0.8;
+    let bulb = Distance::custom(move |p| {
+        mandelbulb(p / scale) * scale
+    })
+    .translate(DVec3::new(3., 1., 0.));
+    world.push(Shapes::Sdf(
+        Sdf::new(bulb, gold).with_bounds(Aabb::new(
+            DVec3::new(2., 0., -1.),
+            DVec3::new(4., 2., 1.),
+        )),
+    ));
+
+    // an infinite lattice of spheres, cut down to a
+    // single row by its bounds
+    let lattice = Distance::Sphere { radius: 0.15 }
+        .repeat(DVec3::splat(0.5))
+        .translate(DVec3::new(0., 0.15, 2.5));
+    world.push(Shapes::Sdf(
+        Sdf::new(lattice, blue).with_bounds(Aabb::new(
+            DVec3::new(-3.2, 0., 2.3),
+            DVec3::new(3.2, 0.35, 2.7),
+        )),
+    ));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 4., 10.))
+        .look_at(DVec3::new(0., 0.6, 0.5))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(35.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use glam::{DVec3, Vec3Swizzles};
+use noise::{Fbm, NoiseFn, Perlin};
+use raytracer::{
+    aabb::Aabb,
+    camera::Camera,
+    material::Material,
+    shapes::{
+        heterogeneous_medium::{
+            Density, HeterogeneousMedium, VoxelGrid,
+        },
+        quad::Quad,
+        quad_box::QuadBox,
+        sphere::Sphere,
+        Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let white = Material::Lambertian {
+        albedo: DVec3::splat(0.73).into(),
+    };
+    let light =
+        Material::DiffuseLight(DVec3::splat(4.).into());
+
+    // floor and a big light above
+    world.push(Shapes::Quad(Quad::new(
+        DVec3::new(-10., 0., -10.),
+        DVec3::new(20., 0., 0.),
+        DVec3::new(0., 0., 20.),
+        white.clone(),
+    )));
+    world.push(Shapes::Quad(Quad::new(
+        DVec3::new(-3., 8., -3.),
+        DVec3::new(6., 0., 0.),
+        DVec3::new(0., 0., 6.),
+        light,
+    )));
+
+    // a smoke plume that widens and thins out as it
+    // rises, broken up by noise
+    let fbm = Fbm::<Perlin>::new(3);
+    let plume = Density::function(move |p: DVec3| {
+        let local = p - DVec3::new(-2.5, 0., 0.);
+        let radius = 0.3 + 0.25 * local.y;
+        let falloff = 1. - local.xz().length() / radius;
+        let noise = fbm.get((local * 2.).to_array());
+        (falloff + 0.6 * noise) * (1. - local.y / 4.)
+    });
+    world.push(Shapes::HeterogeneousMedium(
+        HeterogeneousMedium::new(
+            Shapes::QuadBox(QuadBox::new(
+                DVec3::new(-3.6, 0., -1.1),
+                DVec3::new(-1.4, 4., 1.1),
+                white.clone(),
+            )),
+            plume,
+            8.,
+            DVec3::splat(0.5).into(),
+        ),
+    ));
+
+    // a cloud stored in a voxel grid
+    let perlin = Perlin::new(11);
+    let cloud_bounds = Aabb::new(
+        DVec3::new(-1.2, 1., -1.2),
+        DVec3::new(1.2, 3.4, 1.2),
+    );
+    let cloud_center = DVec3::new(0., 2.2, 0.);
+    let cloud = VoxelGrid::from_fn(
+        [48, 48, 48],
+        cloud_bounds,
+        |p| {
+            let falloff =
+                1. - (p - cloud_center).length() / 1.2;
+            2. * falloff
+                + 0.5 * perlin.get((p * 3.).to_array())
+        },
+    );
+    world.push(Shapes::HeterogeneousMedium(
+        HeterogeneousMedium::new(
+            Shapes::Sphere(Sphere::new(
+                cloud_center,
+                1.2,
+                white.clone(),
+            )),
+            Density::Grid(cloud),
+            10.,
+            DVec3::splat(0.9).into(),
+        ),
+    ));
+
+    // a box of turbulent mist
+    world.push(Shapes::HeterogeneousMedium(
+        HeterogeneousMedium::new(
+            Shapes::QuadBox(QuadBox::new(
+                DVec3::new(1.6, 0., -1.),
+                DVec3::new(3.6, 2., 1.),
+                white,
+            )),
+            Density::Texture(Texture::Turbulence(
+                Perlin::new(5),
+            )),
+            3.,
+            DVec3::new(0.8, 0.6, 0.4).into(),
+        ),
+    ));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 3., 11.))
+        .look_at(DVec3::new(0., 1.8, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(500)
+        .max_depth(50)
+        .vfov(35.)
+        .background(DVec3::splat(0.05))
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
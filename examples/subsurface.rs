@@ -0,0 +1,80 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{a_box, plane::Plane, sphere::Sphere, Shapes},
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian {
+            albedo: DVec3::splat(0.4).into(),
+        },
+    )));
+    let translucent =
+        |albedo: DVec3, mean_free_path: f64| {
+            Material::Subsurface {
+                albedo: albedo.into(),
+                mean_free_path,
+                index_of_refraction: 1.4,
+                roughness: 0.3,
+            }
+        };
+    let materials = [
+        // a diffuse sphere to compare against
+        Material::Lambertian {
+            albedo: DVec3::new(0.85, 0.6, 0.5).into(),
+        },
+        // skin
+        translucent(DVec3::new(0.85, 0.6, 0.5), 0.1),
+        // marble
+        translucent(DVec3::new(0.9, 0.9, 0.88), 0.2),
+        // wax
+        translucent(DVec3::new(0.9, 0.75, 0.4), 0.3),
+        // milk
+        translucent(DVec3::new(0.95, 0.95, 0.93), 0.05),
+    ];
+    for (i, material) in materials.into_iter().enumerate() {
+        world.push(Shapes::Sphere(Sphere::new(
+            DVec3::new(-4. + 2. * i as f64, 0.7, 0.),
+            0.7,
+            material,
+        )));
+    }
+
+    // a thin slab of jade, with a lamp behind it
+    world.push(Shapes::Box(a_box::Box::new(
+        DVec3::new(0., 1., -2.),
+        DVec3::new(2., 1., 0.1),
+        translucent(DVec3::new(0.3, 0.8, 0.4), 0.5),
+    )));
+    world.push(Shapes::Sphere(Sphere::new(
+        DVec3::new(0., 1., -3.),
+        0.4,
+        Material::DiffuseLight(DVec3::splat(20.).into()),
+    )));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 2.5, 9.))
+        .look_at(DVec3::new(0., 0.8, -0.5))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(200)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
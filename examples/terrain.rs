@@ -0,0 +1,59 @@
+use glam::DVec3;
+use noise::{Fbm, MultiFractal, Perlin};
+use raytracer::{
+    aabb::Aabb,
+    camera::Camera,
+    material::Material,
+    shapes::{
+        heightfield::Heightfield, plane::Plane, Shapes,
+    },
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let ground = Material::Lambertian {
+        albedo: DVec3::new(0.35, 0.45, 0.2).into(),
+    };
+    let water = Material::Metal {
+        albedo: DVec3::new(0.4, 0.5, 0.6),
+        fuzz: 0.02,
+    };
+
+    let noise = Fbm::<Perlin>::new(7).set_octaves(6);
+    world.push(Shapes::Heightfield(
+        Heightfield::from_noise(
+            &noise,
+            3.,
+            256,
+            256,
+            Aabb::new(
+                DVec3::new(-10., 0., -10.),
+                DVec3::new(10., 4., 10.),
+            ),
+            ground,
+        ),
+    ));
+    world.push(Shapes::Plane(Plane::ground(1.6, water)));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 7., 14.))
+        .look_at(DVec3::new(0., 1.5, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .vfov(45.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use glam::DVec3;
+use raytracer::{
+    camera::Camera,
+    material::Material,
+    shapes::{
+        a_box, cylinder::Cylinder, plane::Plane,
+        sphere::Sphere, Shapes,
+    },
+    textures::Texture,
+};
+use std::{io, path::Path};
+
+fn main() -> io::Result<()> {
+    let mut world = vec![];
+
+    let checker = Texture::Checkered {
+        even: DVec3::splat(0.2),
+        odd: DVec3::splat(0.9),
+        scale: 0.5,
+    };
+    world.push(Shapes::Plane(Plane::ground(
+        0.,
+        Material::Lambertian { albedo: checker },
+    )));
+
+    // window glass: faintly green when thin, deep green
+    // when seen through a thick block
+    let green_glass = Material::Dielectric {
+        index_of_refraction: 1.5,
+    }
+    .with_transmission_color(
+        DVec3::new(0.7, 0.9, 0.75),
+        0.5,
+    );
+    for (i, thickness) in
+        [0.1, 0.4, 1.2].into_iter().enumerate()
+    {
+        world.push(Shapes::Box(a_box::Box::new(
+            DVec3::new(-3.5 + 1.5 * i as f64, 0.8, 0.),
+            DVec3::new(0.5, 0.8, thickness / 2.),
+            green_glass.clone(),
+        )));
+    }
+
+    // a column of red wine
+    world.push(Shapes::Cylinder(Cylinder::new(
+        DVec3::new(1.5, 0., 0.),
+        DVec3::new(1.5, 1.6, 0.),
+        0.5,
+        Material::Dielectric {
+            index_of_refraction: 1.34,
+        }
+        .with_transmission_color(
+            DVec3::new(0.6, 0.05, 0.1),
+            0.3,
+        ),
+    )));
+
+    // frosted blue glass
+    world.push(Shapes::Sphere(Sphere::new(
+        DVec3::new(3.3, 0.8, 0.),
+        0.8,
+        Material::RoughDielectric {
+            index_of_refraction: 1.5,
+            roughness: 0.2,
+        }
+        .with_absorption(DVec3::new(1.5, 0.6, 0.1)),
+    )));
+
+    let camera = Camera::init()
+        .image_width(800)
+        .aspect_ratio(16.0 / 9.0)
+        .look_from(DVec3::new(0., 3., 10.))
+        .look_at(DVec3::new(0., 0.7, 0.))
+        .vup(DVec3::Y)
+        .samples_per_pixel(200)
+        .max_depth(50)
+        .vfov(40.)
+        .build();
+
+    let filename = Path::new(file!())
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap()
+        .trim_end_matches(".rs");
+    camera.render_to_disk(filename, world)?;
+
+    Ok(())
+}
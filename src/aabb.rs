@@ -0,0 +1,113 @@
+use glam::{DMat4, DVec3};
+use std::ops::Range;
+
+use crate::ray::Ray;
+
+/// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: DVec3,
+    pub max: DVec3,
+}
+
+impl Aabb {
+    /// The box spanned by two opposite corners, in any
+    /// order.
+    pub fn new(a: DVec3, b: DVec3) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+    /// The smallest box containing every point
+    pub fn from_points(
+        points: impl IntoIterator<Item = DVec3>,
+    ) -> Self {
+        points.into_iter().fold(
+            Self {
+                min: DVec3::INFINITY,
+                max: DVec3::NEG_INFINITY,
+            },
+            |aabb, point| Self {
+                min: aabb.min.min(point),
+                max: aabb.max.max(point),
+            },
+        )
+    }
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+    /// The box where both boxes overlap. It is empty, with
+    /// `min` past `max`, if they don't overlap at all.
+    pub fn intersection(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        }
+    }
+    /// Grow any side thinner than `delta` so that flat
+    /// shapes such as quads still have some volume.
+    pub fn pad(&self, delta: f64) -> Self {
+        let size = self.max - self.min;
+        let grow = DVec3::select(
+            size.cmplt(DVec3::splat(delta)),
+            DVec3::splat(delta / 2.),
+            DVec3::ZERO,
+        );
+        Self {
+            min: self.min - grow,
+            max: self.max + grow,
+        }
+    }
+    pub fn corners(&self) -> [DVec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            DVec3::new(a.x, a.y, a.z),
+            DVec3::new(b.x, a.y, a.z),
+            DVec3::new(a.x, b.y, a.z),
+            DVec3::new(b.x, b.y, a.z),
+            DVec3::new(a.x, a.y, b.z),
+            DVec3::new(b.x, a.y, b.z),
+            DVec3::new(a.x, b.y, b.z),
+            DVec3::new(b.x, b.y, b.z),
+        ]
+    }
+    /// The box around this box's corners after they have
+    /// been moved by `matrix`.
+    pub fn transform(&self, matrix: &DMat4) -> Self {
+        Self::from_points(
+            self.corners().map(|corner| {
+                matrix.transform_point3(corner)
+            }),
+        )
+    }
+    /// The range of `t` for which the ray is inside the
+    /// box, clipped to `interval`, or `None` if the ray
+    /// misses the box inside the interval.
+    pub fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<Range<f64>> {
+        let inv_direction = ray.direction.recip();
+        let t0 = (self.min - ray.origin) * inv_direction;
+        let t1 = (self.max - ray.origin) * inv_direction;
+
+        // NaN comes from rays parallel to a slab starting
+        // on its boundary, which we count as a hit
+        let near = t0.min(t1);
+        let far = t0.max(t1);
+        let start = interval
+            .start
+            .max(near.x)
+            .max(near.y)
+            .max(near.z);
+        let end =
+            interval.end.min(far.x).min(far.y).min(far.z);
+
+        (start <= end).then_some(start..end)
+    }
+}
@@ -0,0 +1,261 @@
+use std::ops::Range;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    ray::{Ray, RayStats},
+};
+
+/// A bounding volume hierarchy over a list of objects, so
+/// that a ray is only tested against the objects whose
+/// boxes it passes through. Objects without a bounding
+/// box, such as planes, are tested against every ray.
+pub struct Bvh<T> {
+    objects: Vec<T>,
+    root: Option<Node>,
+    unbounded: Vec<usize>,
+}
+
+enum Node {
+    Leaf {
+        aabb: Aabb,
+        object: usize,
+    },
+    Branch {
+        aabb: Aabb,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+impl<T> Bvh<T>
+where
+    T: Hittable,
+{
+    pub fn new(objects: Vec<T>) -> Self {
+        let mut bounded = vec![];
+        let mut unbounded = vec![];
+        for (index, object) in objects.iter().enumerate() {
+            match object.bounding_box() {
+                Some(aabb) => bounded.push((index, aabb)),
+                None => unbounded.push(index),
+            }
+        }
+        Self {
+            objects,
+            root: (!bounded.is_empty())
+                .then(|| Node::build(bounded)),
+            unbounded,
+        }
+    }
+}
+
+impl Node {
+    /// Split `items` in half along the longest axis of
+    /// their centers until each leaf holds one object.
+    /// `items` can't be empty.
+    fn build(mut items: Vec<(usize, Aabb)>) -> Self {
+        let aabb = items
+            .iter()
+            .map(|(_, aabb)| *aabb)
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+        if items.len() == 1 {
+            return Node::Leaf {
+                aabb,
+                object: items[0].0,
+            };
+        }
+
+        let centers = items
+            .iter()
+            .map(|(_, aabb)| (aabb.min + aabb.max) / 2.);
+        let spread = Aabb::from_points(centers);
+        let size = spread.max - spread.min;
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+        items.sort_by(|(_, a), (_, b)| {
+            (a.min[axis] + a.max[axis])
+                .total_cmp(&(b.min[axis] + b.max[axis]))
+        });
+        let right = items.split_off(items.len() / 2);
+        Node::Branch {
+            aabb,
+            left: Box::new(Node::build(items)),
+            right: Box::new(Node::build(right)),
+        }
+    }
+    fn aabb(&self) -> &Aabb {
+        match self {
+            Node::Leaf { aabb, .. } => aabb,
+            Node::Branch { aabb, .. } => aabb,
+        }
+    }
+    fn hit<T>(
+        &self,
+        objects: &[T],
+        ray: &Ray,
+        interval: Range<f64>,
+        stats: &mut RayStats,
+    ) -> Option<HitRecord>
+    where
+        T: Hittable,
+    {
+        stats.bvh_nodes += 1;
+        self.aabb().hit(ray, interval.clone())?;
+        match self {
+            Node::Leaf { object, .. } => objects[*object]
+                .hit_with_stats(ray, interval, stats),
+            Node::Branch { left, right, .. } => {
+                let left = left.hit(
+                    objects,
+                    ray,
+                    interval.clone(),
+                    stats,
+                );
+                let end = left
+                    .as_ref()
+                    .map_or(interval.end, |hit| hit.t);
+                right
+                    .hit(
+                        objects,
+                        ray,
+                        interval.start..end,
+                        stats,
+                    )
+                    .or(left)
+            }
+        }
+    }
+}
+
+impl<T> Hittable for Bvh<T>
+where
+    T: Hittable + Sync,
+{
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        self.hit_with_stats(
+            ray,
+            interval,
+            &mut RayStats::default(),
+        )
+    }
+    fn hit_with_stats(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+        stats: &mut RayStats,
+    ) -> Option<HitRecord> {
+        let mut closest =
+            self.root.as_ref().and_then(|root| {
+                root.hit(
+                    &self.objects,
+                    ray,
+                    interval.clone(),
+                    stats,
+                )
+            });
+        for &index in &self.unbounded {
+            let end = closest
+                .as_ref()
+                .map_or(interval.end, |hit| hit.t);
+            if let Some(hit_record) = self.objects[index]
+                .hit_with_stats(
+                    ray,
+                    interval.start..end,
+                    stats,
+                )
+            {
+                closest = Some(hit_record);
+            }
+        }
+        closest
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        if self.unbounded.is_empty() {
+            self.root.as_ref().map(|root| *root.aabb())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::Material,
+        shapes::{plane::Plane, sphere::Sphere, Shapes},
+    };
+    use glam::DVec3;
+    use rand::Rng;
+
+    #[test]
+    fn hits_match_a_linear_scan() {
+        let mut rng = rand::thread_rng();
+        let spheres: Vec<(DVec3, f64)> = (0..200)
+            .map(|_| {
+                let center = DVec3::new(
+                    rng.gen_range(-10.0..10.),
+                    rng.gen_range(-10.0..10.),
+                    rng.gen_range(-10.0..10.),
+                );
+                (center, rng.gen_range(0.1..1.))
+            })
+            .collect();
+        // a plane too, which the tree can't hold
+        let world = || {
+            let material = Material::Lambertian {
+                albedo: DVec3::ONE.into(),
+            };
+            let mut world: Vec<Shapes> = spheres
+                .iter()
+                .map(|&(center, radius)| {
+                    Shapes::Sphere(Sphere::new(
+                        center,
+                        radius,
+                        material.clone(),
+                    ))
+                })
+                .collect();
+            world.push(Shapes::Plane(Plane::ground(
+                -5., material,
+            )));
+            world
+        };
+        let list = world();
+        let bvh = Bvh::new(world());
+
+        for _ in 0..10_000 {
+            let ray = Ray {
+                origin: DVec3::new(
+                    rng.gen_range(-15.0..15.),
+                    rng.gen_range(-15.0..15.),
+                    rng.gen_range(-15.0..15.),
+                ),
+                direction: DVec3::new(
+                    rng.gen_range(-1.0..1.),
+                    rng.gen_range(-1.0..1.),
+                    rng.gen_range(-1.0..1.),
+                ),
+                time: 0.,
+            };
+            let expected = list
+                .hit(&ray, 0.001..f64::INFINITY)
+                .map(|hit| hit.t);
+            let found = bvh
+                .hit(&ray, 0.001..f64::INFINITY)
+                .map(|hit| hit.t);
+            assert_eq!(expected, found);
+        }
+    }
+}
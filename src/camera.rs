@@ -1,13 +1,29 @@
-use crate::{hittable::Hittable, ray::Ray};
+use crate::{
+    hittable::Hittable,
+    ray::{Ray, RayStats},
+    spectrum::{spectrum_to_rgb, Wavelengths},
+};
 use glam::DVec3;
-use indicatif::ParallelProgressIterator;
-use itertools::Itertools;
+use image::RgbImage;
+use indicatif::ProgressBar;
 use rand::prelude::*;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use std::{fs, io};
+use std::{
+    fs, io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
 
 mod builder;
+mod observer;
+mod region;
+mod tiles;
 use builder::*;
+pub use observer::*;
+pub use region::*;
+pub use tiles::*;
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -31,6 +47,9 @@ pub struct Camera {
     pixel00_loc: DVec3,
     /// Count of random samples for each pixel
     samples_per_pixel: u32,
+    /// Number of sweeps over the image the samples are
+    /// split across
+    passes: u32,
     /// Maximum number of ray bounces into scene
     max_depth: u32,
     /// Vertical view angle (field of view)
@@ -58,6 +77,17 @@ pub struct Camera {
     /// Defocus disk vertical radius
     defocus_disk_v: DVec3,
     background: Option<DVec3>,
+    /// The rectangle of pixels that gets rendered
+    region: PixelRect,
+    /// Width and height of the square tiles handed to
+    /// each render thread
+    tile_size: u32,
+    /// Order in which tiles get rendered
+    tile_order: TileOrder,
+    /// Stops the render when cancelled
+    cancel_token: CancelToken,
+    /// Trace sampled wavelengths instead of RGB
+    spectral: bool,
 }
 
 impl Default for Camera {
@@ -69,6 +99,15 @@ impl Camera {
     pub fn init() -> CameraBuilder {
         CameraBuilder::default()
     }
+    /// A handle that stops this camera's renders when
+    /// cancelled. Renders that are stopped return an
+    /// error of kind [`io::ErrorKind::Interrupted`].
+    /// Cancelling between renders stops the next one
+    /// before it starts. The token is reset when a render
+    /// ends, so each cancel only stops one render.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel_token.clone()
+    }
 
     fn get_ray(&self, i: i32, j: i32) -> Ray {
         let mut rng = rand::thread_rng();
@@ -111,6 +150,9 @@ impl Camera {
         (px * self.pixel_delta_u)
             + (py * self.pixel_delta_v)
     }
+    /// Render the camera's region and write it to
+    /// `{filename}.ppm`. If the region is smaller than the
+    /// image, the output is cropped to the region.
     pub fn render_to_disk<T>(
         &self,
         filename: &str,
@@ -119,55 +161,10 @@ impl Camera {
     where
         T: Hittable + std::marker::Sync,
     {
-        let pixels = (0..self.image_height)
-            .cartesian_product(0..self.image_width)
-            .collect::<Vec<(u32, u32)>>()
-            .into_par_iter()
-            .progress_count(
-                self.image_height as u64
-                    * self.image_width as u64,
-            )
-            .map(|(y, x)| {
-                let scale_factor =
-                    (self.samples_per_pixel as f64).recip();
-
-                let multisampled_pixel_color = (0..self
-                    .samples_per_pixel)
-                    .into_iter()
-                    .map(|_| {
-                        self.get_ray(x as i32, y as i32)
-                            .color(
-                                self.max_depth,
-                                &world,
-                                &self.background,
-                            )
-                    })
-                    .sum::<DVec3>()
-                    * scale_factor;
-
-                // * 256.
-                let color = DVec3 {
-                    x: linear_to_gamma(
-                        multisampled_pixel_color.x,
-                    ),
-                    y: linear_to_gamma(
-                        multisampled_pixel_color.y,
-                    ),
-                    z: linear_to_gamma(
-                        multisampled_pixel_color.z,
-                    ),
-                }
-                .clamp(
-                    DVec3::splat(0.),
-                    DVec3::splat(0.999),
-                ) * 256.;
-                format!(
-                    "{} {} {}",
-                    color.x as u8,
-                    color.y as u8,
-                    color.z as u8
-                )
-            })
+        let pixels = self
+            .render(&world, &ProgressBar::new(0))?
+            .iter()
+            .map(|[r, g, b]| format!("{r} {g} {b}"))
             .collect::<Vec<String>>()
             .join("\n");
         fs::write(
@@ -178,12 +175,254 @@ impl Camera {
 {}
 {pixels}
 ",
-                self.image_width,
-                self.image_height,
+                self.region.width,
+                self.region.height,
                 self.max_value
             ),
         )
     }
+    /// Render the camera's region and composite it into
+    /// an existing image, leaving every pixel outside of
+    /// the region untouched. The image must have the same
+    /// dimensions as the camera.
+    pub fn render_into<T>(
+        &self,
+        image: &mut RgbImage,
+        world: T,
+    ) -> io::Result<()>
+    where
+        T: Hittable + std::marker::Sync,
+    {
+        if image.dimensions()
+            != (self.image_width, self.image_height)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "image is {}x{} but camera renders {}x{}",
+                    image.width(),
+                    image.height(),
+                    self.image_width,
+                    self.image_height
+                ),
+            ));
+        }
+        let pixels =
+            self.render(&world, &ProgressBar::new(0))?;
+        for (i, pixel) in pixels.into_iter().enumerate() {
+            let i = i as u32;
+            image.put_pixel(
+                self.region.x + i % self.region.width,
+                self.region.y + i / self.region.width,
+                image::Rgb(pixel),
+            );
+        }
+        Ok(())
+    }
+    /// Render every pixel in the camera's region and
+    /// return them in row-major order, reporting progress
+    /// to `observer`.
+    ///
+    /// The samples for each pixel are split across the
+    /// camera's passes. Within a pass, tiles are started in
+    /// the camera's [`TileOrder`], but finish in whatever
+    /// order the render threads get to them.
+    pub fn render<T, O>(
+        &self,
+        world: &T,
+        observer: &O,
+    ) -> io::Result<Vec<[u8; 3]>>
+    where
+        T: Hittable + std::marker::Sync,
+        O: RenderObserver,
+    {
+        if self.cancel_token.is_cancelled() {
+            return Err(self.cancelled());
+        }
+        let start = Instant::now();
+        let region = self.region;
+        let tiles =
+            tiles(region, self.tile_size, self.tile_order);
+        // the sum of every sample taken so far, per pixel
+        let accumulated = Mutex::new(vec![
+            DVec3::ZERO;
+            region.pixel_count()
+                as usize
+        ]);
+        let stats = Mutex::new(RayStats::default());
+        let mut samples_taken = 0;
+        let mut pixels = vec![];
+
+        observer.started(region, self.passes);
+        for pass in 0..self.passes {
+            let samples = self.samples_in_pass(pass);
+            samples_taken += samples;
+            let scale_factor =
+                (samples_taken as f64).recip();
+            let next_tile = AtomicUsize::new(0);
+
+            // each worker pulls the next tile off of the
+            // shared list, so tiles are started in order
+            rayon::scope(|scope| {
+                for _ in 0..rayon::current_num_threads() {
+                    scope.spawn(|_| loop {
+                        let index = next_tile
+                            .fetch_add(1, Ordering::Relaxed);
+                        let Some(&tile) = tiles.get(index)
+                        else {
+                            break;
+                        };
+                        let Some((sums, tile_stats)) = self
+                            .render_tile(tile, samples, world)
+                        else {
+                            break;
+                        };
+                        let tile_pixels = {
+                            let mut accumulated =
+                                accumulated.lock().unwrap();
+                            tile.pixels()
+                                .zip(sums)
+                                .map(|((x, y), sum)| {
+                                    let pixel = &mut accumulated
+                                        [self.region_index(x, y)];
+                                    *pixel += sum;
+                                    to_rgb(*pixel * scale_factor)
+                                })
+                                .collect::<Vec<[u8; 3]>>()
+                        };
+                        *stats.lock().unwrap() += tile_stats;
+                        observer.tile_complete(
+                            tile,
+                            pass,
+                            &tile_pixels,
+                        );
+                    });
+                }
+            });
+
+            if self.cancel_token.is_cancelled() {
+                return Err(self.cancelled());
+            }
+            pixels = accumulated
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|sum| to_rgb(*sum * scale_factor))
+                .collect();
+            observer.pass_complete(pass, &pixels);
+        }
+
+        // a cancel that came in after the last pass is
+        // too late to stop this render, and shouldn't stop
+        // the next one
+        self.cancel_token.reset();
+        observer.finished(&RenderStats {
+            rays: stats.into_inner().unwrap(),
+            elapsed: start.elapsed(),
+        });
+        Ok(pixels)
+    }
+    /// Reset the cancel token so it can stop another
+    /// render, and return the error for this one
+    fn cancelled(&self) -> io::Error {
+        self.cancel_token.reset();
+        io::Error::new(
+            io::ErrorKind::Interrupted,
+            "render was cancelled",
+        )
+    }
+    /// How many of the samples for each pixel are taken
+    /// in `pass`. Any remainder is spread over the earliest
+    /// passes.
+    fn samples_in_pass(&self, pass: u32) -> u32 {
+        self.samples_per_pixel / self.passes
+            + (pass < self.samples_per_pixel % self.passes)
+                as u32
+    }
+    /// Index of the pixel at (x, y) in a row-major buffer
+    /// covering the camera's region.
+    fn region_index(&self, x: u32, y: u32) -> usize {
+        (y - self.region.y) as usize
+            * self.region.width as usize
+            + (x - self.region.x) as usize
+    }
+    /// Take `samples` samples for every pixel in a tile
+    /// and return their sums, or return `None` if the
+    /// render was cancelled part way through.
+    fn render_tile<T>(
+        &self,
+        tile: PixelRect,
+        samples: u32,
+        world: &T,
+    ) -> Option<(Vec<DVec3>, RayStats)>
+    where
+        T: Hittable + std::marker::Sync,
+    {
+        let mut stats = RayStats::default();
+        let mut sums =
+            Vec::with_capacity(tile.pixel_count() as usize);
+        for y in tile.y..tile.y + tile.height {
+            if self.cancel_token.is_cancelled() {
+                return None;
+            }
+            for x in tile.x..tile.x + tile.width {
+                sums.push(self.sample_pixel(
+                    x, y, samples, world, &mut stats,
+                ));
+            }
+        }
+        Some((sums, stats))
+    }
+    /// Sum the colors of `samples` random rays through
+    /// the pixel at (x, y).
+    fn sample_pixel<T>(
+        &self,
+        x: u32,
+        y: u32,
+        samples: u32,
+        world: &T,
+        stats: &mut RayStats,
+    ) -> DVec3
+    where
+        T: Hittable + std::marker::Sync,
+    {
+        stats.primary_rays += samples as u64;
+        (0..samples)
+            .map(|_| {
+                let ray = self.get_ray(x as i32, y as i32);
+                if !self.spectral {
+                    return ray.color_with_stats(
+                        self.max_depth,
+                        world,
+                        &self.background,
+                        stats,
+                    );
+                }
+                let mut wavelengths = Wavelengths::sample();
+                let radiance = ray.spectral_color(
+                    self.max_depth,
+                    world,
+                    &self.background,
+                    &mut wavelengths,
+                    stats,
+                );
+                spectrum_to_rgb(radiance, &wavelengths)
+            })
+            .sum::<DVec3>()
+    }
+}
+
+/// Gamma correct a linear color and quantize it to
+/// 8 bits per channel.
+fn to_rgb(color: DVec3) -> [u8; 3] {
+    let color = DVec3 {
+        x: linear_to_gamma(color.x),
+        y: linear_to_gamma(color.y),
+        z: linear_to_gamma(color.z),
+    }
+    .clamp(DVec3::splat(0.), DVec3::splat(0.999))
+        * 256.;
+    [color.x as u8, color.y as u8, color.z as u8]
 }
 
 fn linear_to_gamma(scalar: f64) -> f64 {
@@ -207,3 +446,50 @@ fn random_in_unit_disk() -> DVec3 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::Shapes;
+
+    /// Counts the tiles it is told about
+    #[derive(Default)]
+    struct TileCounter(AtomicUsize);
+
+    impl RenderObserver for TileCounter {
+        fn tile_complete(
+            &self,
+            _tile: PixelRect,
+            _pass: u32,
+            _pixels: &[[u8; 3]],
+        ) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn cancelled_tokens_stop_the_next_render() {
+        let cancel_token = CancelToken::new();
+        let camera = Camera::init()
+            .image_width(16)
+            .samples_per_pixel(1)
+            .cancel_token(cancel_token.clone())
+            .build();
+        let world: Vec<Shapes> = vec![];
+
+        cancel_token.cancel();
+        let tiles = TileCounter::default();
+        let error =
+            camera.render(&world, &tiles).unwrap_err();
+        assert_eq!(
+            error.kind(),
+            io::ErrorKind::Interrupted
+        );
+        assert_eq!(tiles.0.load(Ordering::Relaxed), 0);
+
+        // the cancel was used up by the render it stopped
+        let tiles = TileCounter::default();
+        assert!(camera.render(&world, &tiles).is_ok());
+        assert!(tiles.0.load(Ordering::Relaxed) > 0);
+    }
+}
@@ -1,6 +1,6 @@
 use glam::DVec3;
 
-use super::Camera;
+use super::{Camera, CancelToken, Region, TileOrder};
 
 pub struct CameraBuilder {
     image_width: u32,
@@ -11,9 +11,15 @@ pub struct CameraBuilder {
     focus_dist: f64,
     defocus_angle: f64,
     samples_per_pixel: u32,
+    passes: u32,
     max_depth: u32,
     vfov: f64,
     background: Option<DVec3>,
+    region: Region,
+    tile_size: u32,
+    tile_order: TileOrder,
+    cancel_token: CancelToken,
+    spectral: bool,
 }
 impl Default for CameraBuilder {
     fn default() -> Self {
@@ -26,9 +32,15 @@ impl Default for CameraBuilder {
             focus_dist: 10.,
             defocus_angle: 0.,
             samples_per_pixel: 100,
+            passes: 1,
             max_depth: 50,
             vfov: 20.,
             background: None,
+            region: Region::Full,
+            tile_size: 16,
+            tile_order: TileOrder::Scanline,
+            cancel_token: CancelToken::new(),
+            spectral: false,
         }
     }
 }
@@ -87,6 +99,13 @@ impl CameraBuilder {
         self.samples_per_pixel = samples_per_pixel;
         self
     }
+    /// Split the samples for each pixel across this many
+    /// passes over the image, so a preview of the whole
+    /// image is available after the first pass.
+    pub fn passes(mut self, passes: u32) -> CameraBuilder {
+        self.passes = passes;
+        self
+    }
     pub fn max_depth(
         mut self,
         max_depth: u32,
@@ -105,6 +124,50 @@ impl CameraBuilder {
         self.background = Some(bg);
         self
     }
+    /// Only render part of the image. Defaults to
+    /// rendering every pixel.
+    pub fn region(
+        mut self,
+        region: Region,
+    ) -> CameraBuilder {
+        self.region = region;
+        self
+    }
+    /// Width and height in pixels of the square tiles
+    /// the image is split into for rendering.
+    pub fn tile_size(
+        mut self,
+        tile_size: u32,
+    ) -> CameraBuilder {
+        self.tile_size = tile_size;
+        self
+    }
+    pub fn tile_order(
+        mut self,
+        tile_order: TileOrder,
+    ) -> CameraBuilder {
+        self.tile_order = tile_order;
+        self
+    }
+    /// Trace light at sampled wavelengths instead of in
+    /// RGB, so that dispersive materials split light into
+    /// colors. Off by default.
+    pub fn spectral(
+        mut self,
+        spectral: bool,
+    ) -> CameraBuilder {
+        self.spectral = spectral;
+        self
+    }
+    /// Stop renders when `cancel_token` is cancelled, so a
+    /// caller can keep a handle before the camera is built
+    pub fn cancel_token(
+        mut self,
+        cancel_token: CancelToken,
+    ) -> CameraBuilder {
+        self.cancel_token = cancel_token;
+        self
+    }
     pub fn build(self) -> Camera {
         let max_value: u8 = 255;
         let image_height: u32 = (self.image_width as f64
@@ -166,6 +229,9 @@ impl CameraBuilder {
             // viewport_upper_left,
             pixel00_loc,
             samples_per_pixel: self.samples_per_pixel,
+            passes: self
+                .passes
+                .clamp(1, self.samples_per_pixel.max(1)),
             max_depth: self.max_depth,
             vfov: self.vfov,
             lookfrom: self.look_from,
@@ -179,6 +245,13 @@ impl CameraBuilder {
             defocus_disk_u,
             defocus_disk_v,
             background: self.background,
+            region: self
+                .region
+                .to_pixels(self.image_width, image_height),
+            tile_size: self.tile_size,
+            tile_order: self.tile_order,
+            cancel_token: self.cancel_token,
+            spectral: self.spectral,
         }
     }
 }
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use indicatif::ProgressBar;
+
+use super::PixelRect;
+use crate::ray::RayStats;
+
+/// Statistics reported when a render finishes
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RenderStats {
+    /// Counters summed over every traced path
+    pub rays: RayStats,
+    /// Wall clock time the render took
+    pub elapsed: Duration,
+}
+
+impl RenderStats {
+    pub fn total_rays(&self) -> u64 {
+        self.rays.rays
+    }
+    pub fn shadow_rays(&self) -> u64 {
+        self.rays.shadow_rays
+    }
+    pub fn rays_per_second(&self) -> f64 {
+        self.rays.rays as f64 / self.elapsed.as_secs_f64()
+    }
+    /// Average number of segments in each path, counting
+    /// the primary ray from the camera.
+    pub fn average_path_length(&self) -> f64 {
+        self.rays.rays as f64
+            / self.rays.primary_rays as f64
+    }
+    /// Average number of BVH nodes tested for each ray
+    /// segment
+    pub fn bvh_nodes_per_ray(&self) -> f64 {
+        self.rays.bvh_nodes as f64 / self.rays.rays as f64
+    }
+}
+
+/// Receives events while a camera renders.
+///
+/// Every method has an empty default, so implementors
+/// only need to handle the events they care about. The
+/// tile and pass events are called from the render
+/// threads.
+pub trait RenderObserver: Sync {
+    /// The render is about to start on `region`, taking
+    /// `passes` sweeps over it.
+    fn started(&self, _region: PixelRect, _passes: u32) {}
+    /// A tile finished rendering during `pass`. `pixels`
+    /// holds the tile's current colors in row-major order.
+    fn tile_complete(
+        &self,
+        _tile: PixelRect,
+        _pass: u32,
+        _pixels: &[[u8; 3]],
+    ) {
+    }
+    /// Every tile finished rendering `pass`. `pixels`
+    /// holds the whole region in row-major order.
+    fn pass_complete(
+        &self,
+        _pass: u32,
+        _pixels: &[[u8; 3]],
+    ) {
+    }
+    /// The render finished without being cancelled.
+    fn finished(&self, _stats: &RenderStats) {}
+}
+
+/// Ignores every event
+impl RenderObserver for () {}
+
+/// Shows a progress bar that counts rendered pixels
+/// across every pass.
+impl RenderObserver for ProgressBar {
+    fn started(&self, region: PixelRect, passes: u32) {
+        self.set_length(
+            region.pixel_count() * passes as u64,
+        );
+    }
+    fn tile_complete(
+        &self,
+        tile: PixelRect,
+        _pass: u32,
+        _pixels: &[[u8; 3]],
+    ) {
+        self.inc(tile.pixel_count());
+    }
+    fn finished(&self, stats: &RenderStats) {
+        self.println(format!(
+            "{} rays and {} shadow rays in {:.2?} ({:.0} rays/s, {:.2} segments per path, {:.1} BVH nodes per ray)",
+            stats.total_rays(),
+            stats.shadow_rays(),
+            stats.elapsed,
+            stats.rays_per_second(),
+            stats.average_path_length(),
+            stats.bvh_nodes_per_ray(),
+        ));
+        self.finish();
+    }
+}
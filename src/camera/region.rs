@@ -0,0 +1,117 @@
+use glam::DVec2;
+
+/// A rectangle of pixels in image coordinates, with
+/// the origin at the top left of the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelRect {
+    pub fn new(
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+    /// Every (x, y) pixel coordinate in the rectangle, in
+    /// row-major order.
+    pub fn pixels(
+        &self,
+    ) -> impl Iterator<Item = (u32, u32)> {
+        let PixelRect {
+            x,
+            y,
+            width,
+            height,
+        } = *self;
+        (y..y + height).flat_map(move |y| {
+            (x..x + width).map(move |x| (x, y))
+        })
+    }
+    pub fn pixel_count(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+    /// Shrink the rectangle so that it fits inside an
+    /// image of the given size.
+    pub fn clamp_to(
+        &self,
+        image_width: u32,
+        image_height: u32,
+    ) -> Self {
+        let x = self.x.min(image_width);
+        let y = self.y.min(image_height);
+        Self {
+            x,
+            y,
+            width: self.width.min(image_width - x),
+            height: self.height.min(image_height - y),
+        }
+    }
+}
+
+/// The part of the image a camera should render.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Region {
+    /// Render every pixel
+    #[default]
+    Full,
+    /// Render a rectangle given in pixel coordinates
+    Pixels(PixelRect),
+    /// Render a crop window given in normalized image
+    /// coordinates, where (0,0) is the top left and
+    /// (1,1) is the bottom right of the image.
+    Window { min: DVec2, max: DVec2 },
+}
+
+impl Region {
+    /// Resolve the region into the pixel rectangle it
+    /// covers on an image of the given size.
+    pub fn to_pixels(
+        &self,
+        image_width: u32,
+        image_height: u32,
+    ) -> PixelRect {
+        match self {
+            Region::Full => PixelRect::new(
+                0,
+                0,
+                image_width,
+                image_height,
+            ),
+            Region::Pixels(rect) => {
+                rect.clamp_to(image_width, image_height)
+            }
+            Region::Window { min, max } => {
+                let size = DVec2::new(
+                    image_width as f64,
+                    image_height as f64,
+                );
+                let min = (min
+                    .clamp(DVec2::ZERO, DVec2::ONE)
+                    * size)
+                    .floor();
+                let max = (max
+                    .clamp(DVec2::ZERO, DVec2::ONE)
+                    * size)
+                    .ceil();
+                PixelRect::new(
+                    min.x as u32,
+                    min.y as u32,
+                    (max.x - min.x).max(0.) as u32,
+                    (max.y - min.y).max(0.) as u32,
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use super::PixelRect;
+
+/// The order in which tiles are handed out to the
+/// render threads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TileOrder {
+    /// Left to right, top to bottom
+    #[default]
+    Scanline,
+    /// Start at the center of the image and spiral
+    /// outwards, so the subject usually shows up first
+    Spiral,
+    /// Follow a Hilbert curve, which keeps neighbouring
+    /// tiles close together in time
+    Hilbert,
+}
+
+/// A handle that can stop a render from another thread.
+///
+/// Cancellation is cooperative: render threads check the
+/// token between rows and finish the row they are on.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+    /// Clear an earlier cancellation so the token can stop
+    /// another render
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Split a region into tiles of at most `tile_size` by
+/// `tile_size` pixels, in the requested order.
+pub fn tiles(
+    region: PixelRect,
+    tile_size: u32,
+    order: TileOrder,
+) -> Vec<PixelRect> {
+    let tile_size = tile_size.max(1);
+    let columns = region.width.div_ceil(tile_size);
+    let rows = region.height.div_ceil(tile_size);
+
+    let cells = match order {
+        TileOrder::Scanline => (0..rows)
+            .flat_map(|row| {
+                (0..columns)
+                    .map(move |column| (column, row))
+            })
+            .collect(),
+        TileOrder::Spiral => spiral(columns, rows),
+        TileOrder::Hilbert => hilbert(columns, rows),
+    };
+
+    cells
+        .into_iter()
+        .map(|(column, row)| {
+            let x = column * tile_size;
+            let y = row * tile_size;
+            PixelRect::new(
+                region.x + x,
+                region.y + y,
+                tile_size.min(region.width - x),
+                tile_size.min(region.height - y),
+            )
+        })
+        .collect()
+}
+
+/// Walk a square spiral outwards from the center cell,
+/// keeping only the cells that fall inside the grid.
+fn spiral(columns: u32, rows: u32) -> Vec<(u32, u32)> {
+    let total = columns as usize * rows as usize;
+    let mut cells = Vec::with_capacity(total);
+    let mut x = (columns as i64 - 1) / 2;
+    let mut y = (rows as i64 - 1) / 2;
+    let (mut dx, mut dy) = (1, 0);
+    let mut leg_length = 1;
+
+    while cells.len() < total {
+        // every two legs of the spiral get one step longer
+        for _ in 0..2 {
+            for _ in 0..leg_length {
+                if (0..columns as i64).contains(&x)
+                    && (0..rows as i64).contains(&y)
+                {
+                    cells.push((x as u32, y as u32));
+                }
+                x += dx;
+                y += dy;
+            }
+            (dx, dy) = (-dy, dx);
+        }
+        leg_length += 1;
+    }
+    cells
+}
+
+/// Order the cells of the grid along a Hilbert curve
+/// covering the smallest power-of-two square around it.
+fn hilbert(columns: u32, rows: u32) -> Vec<(u32, u32)> {
+    let side = columns.max(rows).max(1).next_power_of_two();
+    let mut cells = (0..rows)
+        .flat_map(|row| {
+            (0..columns).map(move |column| (column, row))
+        })
+        .collect::<Vec<_>>();
+    cells.sort_by_key(|&(x, y)| hilbert_index(side, x, y));
+    cells
+}
+
+/// Distance along the Hilbert curve of a `side` by `side`
+/// grid at which the cell (x, y) is visited.
+fn hilbert_index(side: u32, mut x: u32, mut y: u32) -> u64 {
+    let mut d = 0;
+    let mut s = side / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u32;
+        let ry = (y & s > 0) as u32;
+        d += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+        // rotate the quadrant so the curve stays connected
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
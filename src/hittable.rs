@@ -1,19 +1,122 @@
-use crate::{material::Material, ray::Ray};
+use crate::{
+    aabb::Aabb,
+    material::Material,
+    ray::{Ray, RayStats},
+};
 use glam::DVec3;
 use std::ops::Range;
 
+/// Stop enumerating hits after this many, in case a shape
+/// keeps reporting the same one. Only shapes that repeat
+/// forever should cross a ray this often, and their
+/// crossings past it are dropped.
+const MAX_HITS: usize = 1024;
+
 pub trait Hittable {
     fn hit(
         &self,
         ray: &Ray,
         interval: Range<f64>,
     ) -> Option<HitRecord>;
+    /// The same as [`Hittable::hit`], but counts the work
+    /// done in `stats`. Only acceleration structures and
+    /// the containers that hold them need to override it.
+    fn hit_with_stats(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+        _stats: &mut RayStats,
+    ) -> Option<HitRecord> {
+        self.hit(ray, interval)
+    }
+    /// A box containing every point the shape can be hit
+    /// at, for any ray time, or `None` if the shape is
+    /// unbounded.
+    fn bounding_box(&self) -> Option<Aabb>;
+    /// Every place the ray crosses the surface inside
+    /// `interval`, nearest first. Shapes that need to know
+    /// which parts of a ray are inside of another shape
+    /// build on this.
+    fn all_hits(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Vec<HitRecord> {
+        let mut hits = vec![];
+        let mut start = interval.start;
+        while hits.len() < MAX_HITS {
+            let Some(hit_record) =
+                self.hit(ray, start..interval.end)
+            else {
+                break;
+            };
+            start = hit_record.t + 0.0001;
+            hits.push(hit_record);
+        }
+        hits
+    }
+    /// The parts of `interval` the ray spends inside this
+    /// shape, which has to be closed. Shapes the ray enters
+    /// and leaves more than once give more than one part.
+    fn inside_intervals(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Vec<Range<f64>> {
+        // start far enough back to see the entry of a
+        // shape the ray starts inside of: where the ray
+        // enters its box, or infinitely far back for
+        // unbounded shapes
+        let start = match self.bounding_box() {
+            Some(aabb) => {
+                let Some(inside) = aabb.hit(
+                    ray,
+                    f64::NEG_INFINITY..interval.end,
+                ) else {
+                    return vec![];
+                };
+                // keep entries right on the box's side
+                inside.start - 0.0001
+            }
+            None => f64::NEG_INFINITY,
+        };
+        let mut intervals = vec![];
+        let mut entry = None;
+        for hit_record in
+            self.all_hits(ray, start..interval.end)
+        {
+            if hit_record.front_face {
+                entry = Some(hit_record.t);
+            } else {
+                let start = entry
+                    .take()
+                    .unwrap_or(f64::NEG_INFINITY)
+                    .max(interval.start);
+                if start < hit_record.t {
+                    intervals.push(start..hit_record.t);
+                }
+            }
+        }
+        // the ray leaves the shape after the interval ends
+        if let Some(start) = entry {
+            intervals.push(
+                start.max(interval.start)..interval.end,
+            );
+        }
+        intervals
+    }
 }
 
 #[derive(Clone)]
 pub struct HitRecord {
     pub point: DVec3,
     pub normal: DVec3,
+    /// The direction u increases in across the surface,
+    /// perpendicular to the normal. Shapes without a
+    /// sensible one give an arbitrary direction.
+    pub tangent: DVec3,
+    /// Perpendicular to both the normal and the tangent
+    pub bitangent: DVec3,
     pub t: f64,
     pub front_face: bool,
     pub material: Material,
@@ -35,16 +138,34 @@ impl HitRecord {
                 ray,
                 &outward_normal,
             );
+        let (tangent, bitangent) =
+            normal.any_orthonormal_pair();
         HitRecord {
             material,
             point,
             normal,
+            tangent,
+            bitangent,
             t,
             front_face,
             u,
             v,
         }
     }
+    /// Point the tangent along `tangent`, usually the
+    /// derivative of the point with respect to u. It is
+    /// made perpendicular to the normal, and ignored if it
+    /// can't be, as at the poles of a sphere.
+    pub fn with_tangent(mut self, tangent: DVec3) -> Self {
+        let tangent = (tangent
+            - self.normal * self.normal.dot(tangent))
+        .normalize_or_zero();
+        if tangent != DVec3::ZERO {
+            self.tangent = tangent;
+            self.bitangent = self.normal.cross(tangent);
+        }
+        self
+    }
     fn calc_face_normal(
         ray: &Ray,
         outward_normal: &DVec3,
@@ -95,4 +216,30 @@ where
 
         hit_record
     }
+    fn hit_with_stats(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+        stats: &mut RayStats,
+    ) -> Option<HitRecord> {
+        let mut closest = None;
+        let mut end = interval.end;
+        for item in self {
+            if let Some(hit_record) = item.hit_with_stats(
+                ray,
+                interval.start..end,
+                stats,
+            ) {
+                end = hit_record.t;
+                closest = Some(hit_record);
+            }
+        }
+        closest
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.iter()
+            .map(|item| item.bounding_box())
+            .reduce(|a, b| Some(a?.union(&b?)))
+            .flatten()
+    }
 }
@@ -1,6 +1,10 @@
+pub mod aabb;
+pub mod bvh;
 pub mod camera;
 pub mod hittable;
 pub mod material;
 pub mod ray;
+pub mod sampling;
 pub mod shapes;
+pub mod spectrum;
 pub mod textures;
@@ -0,0 +1,243 @@
+use glam::DVec3;
+use rand::Rng;
+
+use super::{
+    microfacet::*, scattered, Material, Scattered, ThinFilm,
+};
+use crate::{hittable::HitRecord, ray::Ray};
+
+/// How many times light can bounce between the base and
+/// the underside of the coat before we give up on it
+const MAX_BOUNCES: usize = 32;
+
+/// A layer of dielectric, like varnish, glaze or a car's
+/// clearcoat, over any other material.
+///
+/// Light that gets through the coat is followed as it
+/// bounces between the base and the underside of the coat
+/// until it escapes, so the light that the coat reflects
+/// back down is not lost, and colored coats darken and
+/// saturate the base the way real ones do. The coat
+/// itself is infinitely thin as far as geometry goes;
+/// `thickness` only sets how far light travels through
+/// it, and so how much it absorbs.
+#[derive(Clone)]
+pub struct Coated {
+    base: Material,
+    index_of_refraction: f64,
+    roughness: f64,
+    thickness: f64,
+    absorption: DVec3,
+    thin_film: Option<ThinFilm>,
+}
+
+impl Coated {
+    /// A smooth, clear coat over `base`
+    pub fn new(
+        base: Material,
+        index_of_refraction: f64,
+    ) -> Self {
+        Self {
+            base,
+            index_of_refraction,
+            roughness: 0.,
+            thickness: 0.01,
+            absorption: DVec3::ZERO,
+            thin_film: None,
+        }
+    }
+    /// From a smooth coat at 0 to very rough at 1
+    pub fn with_roughness(
+        mut self,
+        roughness: f64,
+    ) -> Self {
+        self.roughness = roughness;
+        self
+    }
+    pub fn with_thickness(
+        mut self,
+        thickness: f64,
+    ) -> Self {
+        self.thickness = thickness;
+        self
+    }
+    /// The absorption coefficient per unit distance of the
+    /// coat for red, green and blue
+    pub fn with_absorption(
+        mut self,
+        absorption: DVec3,
+    ) -> Self {
+        self.absorption = absorption;
+        self
+    }
+
+    /// Cover the coat with a thin film, which makes its
+    /// reflections iridescent
+    pub fn with_thin_film(
+        mut self,
+        thin_film: ThinFilm,
+    ) -> Self {
+        self.thin_film = Some(thin_film);
+        self
+    }
+
+    pub(super) fn has_thin_film(&self) -> bool {
+        self.thin_film.is_some()
+    }
+
+    pub(super) fn base(&self) -> &Material {
+        &self.base
+    }
+
+    pub(super) fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+        wavelength: Option<f64>,
+    ) -> Option<Scattered> {
+        // A coat doesn't make an object any less solid, so
+        // anything inside of it is up to the base.
+        if !hit_record.front_face {
+            return self.base.scatter_with_wavelength(
+                r_in, hit_record, wavelength,
+            );
+        }
+
+        let basis = Basis::new(hit_record.normal);
+        let wo = basis.local(-r_in.direction.normalize());
+        if wo.z <= 0. {
+            return None;
+        }
+        let alpha = roughness_to_alpha(self.roughness);
+
+        // enter the coat, unless it reflects us away
+        let (w, reflected, weight) = self.interface(
+            wo,
+            alpha,
+            [1., self.index_of_refraction],
+            hit_record,
+            wavelength,
+        )?;
+        if reflected {
+            return Some(scattered(
+                r_in,
+                hit_record,
+                basis.world(w),
+                weight,
+            ));
+        }
+        let mut attenuation = weight;
+        let mut w = w;
+
+        for _ in 0..MAX_BOUNCES {
+            attenuation *= self.transmittance(w);
+
+            // scatter off of the base. Directions are
+            // still in world space for it, and whatever it
+            // sends down into itself is lost.
+            let base = self.base.scatter_with_wavelength(
+                &Ray {
+                    origin: hit_record.point,
+                    direction: basis.world(w),
+                    time: r_in.time,
+                },
+                hit_record,
+                wavelength,
+            )?;
+            attenuation *= base.attenuation;
+            w = basis.local(
+                base.scattered.direction.normalize(),
+            );
+            if w.z <= 0. {
+                return None;
+            }
+
+            attenuation *= self.transmittance(w);
+
+            // Meet the coat from below, looking at it with
+            // z flipped so that it faces the light.
+            let (w_flipped, reflected, weight) = self
+                .interface(
+                    DVec3::new(-w.x, -w.y, w.z),
+                    alpha,
+                    [self.index_of_refraction, 1.],
+                    hit_record,
+                    wavelength,
+                )?;
+            attenuation *= weight;
+            w = DVec3::new(
+                w_flipped.x,
+                w_flipped.y,
+                -w_flipped.z,
+            );
+            if !reflected {
+                return Some(scattered(
+                    r_in,
+                    hit_record,
+                    basis.world(w),
+                    attenuation,
+                ));
+            }
+
+            // Russian roulette, so that dark bases don't
+            // keep us bouncing
+            let survival =
+                attenuation.max_element().min(1.);
+            if survival < rand::thread_rng().gen::<f64>() {
+                return None;
+            }
+            attenuation /= survival;
+        }
+        None
+    }
+
+    /// Reflect off of or refract through the top of the
+    /// coat for light leaving in the local direction `wo`,
+    /// between media with the indices of refraction in
+    /// `indices`, picking which by the Fresnel term. Gives
+    /// the new direction, whether it reflected, and its
+    /// weight.
+    fn interface(
+        &self,
+        wo: DVec3,
+        alpha: f64,
+        [outside, inside]: [f64; 2],
+        hit_record: &HitRecord,
+        wavelength: Option<f64>,
+    ) -> Option<(DVec3, bool, DVec3)> {
+        let refraction_ratio = outside / inside;
+        let (wi, weight) = match &self.thin_film {
+            Some(thin_film) => sample_rough_interface_with(
+                wo,
+                refraction_ratio,
+                alpha,
+                |cos_theta| {
+                    thin_film.choose_reflection(
+                        cos_theta, outside, inside,
+                        wavelength, hit_record,
+                    )
+                },
+            )?,
+            None => {
+                let (wi, weight) = sample_rough_interface(
+                    wo,
+                    refraction_ratio,
+                    alpha,
+                )?;
+                (wi, DVec3::splat(weight))
+            }
+        };
+        Some((wi, wi.z > 0., weight))
+    }
+
+    /// The fraction of light travelling in the local
+    /// direction `w` that makes it through the coat
+    fn transmittance(&self, w: DVec3) -> DVec3 {
+        let distance = self.thickness / w.z.abs();
+        DVec3::new(
+            (-self.absorption.x * distance).exp(),
+            (-self.absorption.y * distance).exp(),
+            (-self.absorption.z * distance).exp(),
+        )
+    }
+}
@@ -0,0 +1,49 @@
+/// How a dielectric's index of refraction changes with
+/// wavelength, which splits white light into colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dispersion {
+    /// Cauchy's equation `n = a + b / λ²`, with `λ` in
+    /// micrometers
+    Cauchy { a: f64, b: f64 },
+    /// The Sellmeier equation with three terms, as listed
+    /// in glass catalogs, with `c` in square micrometers
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Schott N-BK7, a common crown glass
+    pub fn bk7() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.03961212, 0.231792344, 1.01046945],
+            c: [0.00600069867, 0.0200179144, 103.560653],
+        }
+    }
+    /// Schott SF11, a dense flint glass that disperses
+    /// strongly
+    pub fn dense_flint() -> Self {
+        Dispersion::Sellmeier {
+            b: [1.73759695, 0.313747346, 1.89878101],
+            c: [0.013188707, 0.0623068142, 155.23629],
+        }
+    }
+    /// The index of refraction at a wavelength in
+    /// nanometers
+    pub fn index_of_refraction(
+        &self,
+        wavelength: f64,
+    ) -> f64 {
+        let micrometers = wavelength / 1000.;
+        let lambda2 = micrometers * micrometers;
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / lambda2,
+            Dispersion::Sellmeier { b, c } => (1.
+                + b.iter()
+                    .zip(c)
+                    .map(|(b, c)| {
+                        b * lambda2 / (lambda2 - c)
+                    })
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+}
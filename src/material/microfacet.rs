@@ -0,0 +1,225 @@
+use glam::{DVec2, DVec3};
+use rand::Rng;
+use std::f64::consts::PI;
+
+use super::reflections::{
+    fresnel_dielectric, reflect, refract,
+};
+
+/// Below this GGX alpha a surface is treated as perfectly
+/// smooth, since the distribution becomes too sharp to
+/// sample reliably.
+pub const SMOOTH_ALPHA: f64 = 1e-3;
+
+/// An orthonormal basis around a surface normal. The
+/// microfacet functions below work in this local space,
+/// where the normal is +z.
+pub struct Basis {
+    tangent: DVec3,
+    bitangent: DVec3,
+    normal: DVec3,
+}
+
+impl Basis {
+    pub fn new(normal: DVec3) -> Self {
+        let (tangent, bitangent) =
+            normal.any_orthonormal_pair();
+        Self {
+            tangent,
+            bitangent,
+            normal,
+        }
+    }
+    /// A basis with x along `tangent`, which has to be
+    /// perpendicular to the normal
+    pub fn from_tangent(
+        normal: DVec3,
+        tangent: DVec3,
+    ) -> Self {
+        Self {
+            tangent,
+            bitangent: normal.cross(tangent),
+            normal,
+        }
+    }
+    pub fn local(&self, v: DVec3) -> DVec3 {
+        DVec3::new(
+            v.dot(self.tangent),
+            v.dot(self.bitangent),
+            v.dot(self.normal),
+        )
+    }
+    pub fn world(&self, v: DVec3) -> DVec3 {
+        v.x * self.tangent
+            + v.y * self.bitangent
+            + v.z * self.normal
+    }
+}
+
+/// Map a perceptual roughness in [0,1] to the GGX alpha
+/// parameter
+pub fn roughness_to_alpha(roughness: f64) -> f64 {
+    roughness * roughness
+}
+
+/// Smith's auxiliary function for the GGX
+/// (Trowbridge-Reitz) distribution, with roughness
+/// `alpha.x` along the local x axis and `alpha.y` along y
+fn lambda(w: DVec3, alpha: DVec2) -> f64 {
+    let alpha2_tan2 = ((alpha.x * w.x).powi(2)
+        + (alpha.y * w.y).powi(2))
+        / (w.z * w.z);
+    if !alpha2_tan2.is_finite() {
+        return 0.;
+    }
+    ((1. + alpha2_tan2).sqrt() - 1.) / 2.
+}
+
+/// Sample a microfacet normal in proportion to how much of
+/// it is visible from the local direction `wo`, following
+/// Heitz's "Sampling the GGX Distribution of Visible
+/// Normals" (2018).
+pub fn sample_visible_normal(
+    wo: DVec3,
+    alpha: f64,
+) -> DVec3 {
+    sample_anisotropic_visible_normal(
+        wo,
+        DVec2::splat(alpha),
+    )
+}
+
+/// The same as [`sample_visible_normal`] for a surface
+/// with roughness `alpha.x` along the local x axis and
+/// `alpha.y` along y
+pub fn sample_anisotropic_visible_normal(
+    wo: DVec3,
+    alpha: DVec2,
+) -> DVec3 {
+    let mut rng = rand::thread_rng();
+
+    // stretch the view direction to the hemisphere
+    // configuration
+    let vh =
+        DVec3::new(alpha.x * wo.x, alpha.y * wo.y, wo.z)
+            .normalize();
+
+    let length_squared = vh.x * vh.x + vh.y * vh.y;
+    let t1 = if length_squared > 0. {
+        DVec3::new(-vh.y, vh.x, 0.) / length_squared.sqrt()
+    } else {
+        DVec3::X
+    };
+    let t2 = vh.cross(t1);
+
+    // a point on the projected half disk
+    let r = rng.gen::<f64>().sqrt();
+    let phi = 2. * PI * rng.gen::<f64>();
+    let p1 = r * phi.cos();
+    let s = 0.5 * (1. + vh.z);
+    let p2 = (1. - s) * (1. - p1 * p1).sqrt()
+        + s * r * phi.sin();
+
+    let nh = p1 * t1
+        + p2 * t2
+        + (1. - p1 * p1 - p2 * p2).max(0.).sqrt() * vh;
+
+    // unstretch back to the ellipsoid configuration
+    DVec3::new(alpha.x * nh.x, alpha.y * nh.y, nh.z.max(0.))
+        .normalize()
+}
+
+/// A microfacet normal for light arriving from `wo`, which
+/// is the macro surface normal when the surface is smooth
+pub fn sample_microfacet_normal(
+    wo: DVec3,
+    alpha: f64,
+) -> DVec3 {
+    if alpha < SMOOTH_ALPHA {
+        DVec3::Z
+    } else {
+        sample_visible_normal(wo, alpha)
+    }
+}
+
+/// The weight of light reflected or refracted from `wo`
+/// to `wi` off of a visible normal sample, which is the
+/// shadowing the sampling doesn't account for
+pub fn shadowing_weight(
+    wo: DVec3,
+    wi: DVec3,
+    alpha: f64,
+) -> f64 {
+    if alpha < SMOOTH_ALPHA {
+        1.
+    } else {
+        anisotropic_shadowing_weight(
+            wo,
+            wi,
+            DVec2::splat(alpha),
+        )
+    }
+}
+
+/// The same as [`shadowing_weight`] for a rough surface
+/// with roughness `alpha.x` along the local x axis and
+/// `alpha.y` along y
+pub fn anisotropic_shadowing_weight(
+    wo: DVec3,
+    wi: DVec3,
+    alpha: DVec2,
+) -> f64 {
+    (1. + lambda(wo, alpha))
+        / (1. + lambda(wo, alpha) + lambda(wi, alpha))
+}
+
+/// Reflect off of or refract through a rough interface
+/// between dielectrics for light leaving in the local
+/// direction `wo`, where `eta` is the index of refraction
+/// on `wo`'s side over the one on the other side. Which
+/// one is picked by the Fresnel term, which leaves only
+/// the shadowing as the weight. Gives the new direction
+/// and its weight, or `None` if the light is lost.
+pub fn sample_rough_interface(
+    wo: DVec3,
+    eta: f64,
+    alpha: f64,
+) -> Option<(DVec3, f64)> {
+    sample_rough_interface_with(
+        wo,
+        eta,
+        alpha,
+        |cos_theta| {
+            let reflected =
+                fresnel_dielectric(cos_theta, eta)
+                    > rand::thread_rng().gen::<f64>();
+            (reflected, DVec3::ONE)
+        },
+    )
+    .map(|(wi, weight)| (wi, weight.x))
+}
+
+/// The same as [`sample_rough_interface`], but `choose`
+/// picks whether to reflect from the cosine between `wo`
+/// and the microfacet normal, and gives a weight for each
+/// color to make up for its choice.
+pub fn sample_rough_interface_with(
+    wo: DVec3,
+    eta: f64,
+    alpha: f64,
+    choose: impl FnOnce(f64) -> (bool, DVec3),
+) -> Option<(DVec3, DVec3)> {
+    let h = sample_microfacet_normal(wo, alpha);
+    let (reflected, weight) = choose(wo.dot(h));
+    let wi = if reflected {
+        reflect(-wo, h)
+    } else {
+        refract(-wo, h, eta)
+    };
+    // light that reflects into the surface or refracts
+    // back out of it is lost
+    if reflected != (wi.z > 0.) {
+        return None;
+    }
+    Some((wi, weight * shadowing_weight(wo, wi, alpha)))
+}
@@ -0,0 +1,30 @@
+use glam::DVec3;
+
+/// How much brighter or darker a rough diffuse surface is
+/// than a Lambertian one, for light from the local
+/// direction `wi` seen from `wo`, using the qualitative
+/// model from Oren and Nayar's "Generalization of
+/// Lambert's Reflectance Model". `sigma` is the standard
+/// deviation of the angle of the surface's facets, in
+/// degrees.
+pub fn oren_nayar(wo: DVec3, wi: DVec3, sigma: f64) -> f64 {
+    let sigma2 = sigma.to_radians().powi(2);
+    let a = 1. - sigma2 / (2. * (sigma2 + 0.33));
+    let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+    let sin_i = (1. - wi.z * wi.z).max(0.).sqrt();
+    let sin_o = (1. - wo.z * wo.z).max(0.).sqrt();
+    // the cosine of the difference in azimuth
+    let cos_phi = if sin_i > 1e-4 && sin_o > 1e-4 {
+        ((wi.x * wo.x + wi.y * wo.y) / (sin_i * sin_o))
+            .max(0.)
+    } else {
+        0.
+    };
+    let (sin_alpha, tan_beta) = if wi.z.abs() > wo.z.abs() {
+        (sin_o, sin_i / wi.z.abs())
+    } else {
+        (sin_i, sin_o / wo.z.abs())
+    };
+    a + b * cos_phi * sin_alpha * tan_beta
+}
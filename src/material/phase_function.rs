@@ -0,0 +1,86 @@
+use glam::DVec3;
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// How a participating medium spreads out the light it
+/// scatters, relative to the direction the light was
+/// travelling in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PhaseFunction {
+    /// The same in every direction
+    #[default]
+    Isotropic,
+    /// A single lobe controlled by `g` in (-1,1): positive
+    /// values scatter forwards, like fog around a light,
+    /// negative values scatter backwards and 0 is
+    /// isotropic.
+    HenyeyGreenstein { g: f64 },
+    /// A blend of a forward and a backward
+    /// Henyey–Greenstein lobe, as used for clouds.
+    /// `forward_weight` is the fraction of light scattered
+    /// by the forward lobe.
+    DoubleHenyeyGreenstein {
+        g_forward: f64,
+        g_backward: f64,
+        forward_weight: f64,
+    },
+    /// Scattering by particles much smaller than the
+    /// wavelength of light, like the air molecules that
+    /// make the sky blue
+    Rayleigh,
+}
+
+impl PhaseFunction {
+    /// Pick a direction for light travelling along
+    /// `direction` to scatter into, with a probability
+    /// proportional to the phase function.
+    pub fn sample(&self, direction: DVec3) -> DVec3 {
+        let mut rng = rand::thread_rng();
+        let cos_theta = match *self {
+            PhaseFunction::Isotropic => {
+                1. - 2. * rng.gen::<f64>()
+            }
+            PhaseFunction::HenyeyGreenstein { g } => {
+                sample_henyey_greenstein(g, rng.gen())
+            }
+            PhaseFunction::DoubleHenyeyGreenstein {
+                g_forward,
+                g_backward,
+                forward_weight,
+            } => {
+                let g = if rng.gen::<f64>() < forward_weight
+                {
+                    g_forward
+                } else {
+                    g_backward
+                };
+                sample_henyey_greenstein(g, rng.gen())
+            }
+            PhaseFunction::Rayleigh => {
+                // invert the CDF (μ³ + 3μ + 4) / 8 with
+                // Cardano's formula
+                let q = 4. * rng.gen::<f64>() - 2.;
+                let root = (q * q + 1.).sqrt();
+                (q + root).cbrt() + (q - root).cbrt()
+            }
+        }
+        .clamp(-1., 1.);
+
+        let phi = 2. * PI * rng.gen::<f64>();
+        let sin_theta = (1. - cos_theta * cos_theta).sqrt();
+        let forward = direction.normalize();
+        let (x, y) = forward.any_orthonormal_pair();
+        sin_theta * (phi.cos() * x + phi.sin() * y)
+            + cos_theta * forward
+    }
+}
+
+/// The cosine of the angle away from the forward direction
+/// for a uniform random number `xi`
+fn sample_henyey_greenstein(g: f64, xi: f64) -> f64 {
+    if g.abs() < 1e-3 {
+        return 1. - 2. * xi;
+    }
+    let s = (1. - g * g) / (1. - g + 2. * g * xi);
+    (1. + g * g - s * s) / (2. * g)
+}
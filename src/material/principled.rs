@@ -0,0 +1,308 @@
+use glam::DVec3;
+use rand::Rng;
+use std::f64::consts::PI;
+
+use super::{
+    microfacet::*, reflections::*, scattered, vectors::*,
+    Scattered,
+};
+use crate::{
+    hittable::HitRecord, ray::Ray, textures::Texture,
+};
+
+/// The index of refraction of the clearcoat layer
+const CLEARCOAT_INDEX_OF_REFRACTION: f64 = 1.5;
+
+/// A single material covering most real world surfaces,
+/// modelled on Disney's "Physically Based Shading at
+/// Disney" (2012). Each parameter can be driven by a
+/// [`Texture`]; parameters that are a single number read
+/// the texture's red channel and are clamped to [0,1].
+///
+/// Light first meets an optional clearcoat, then either a
+/// metal, a rough glass, or a specular layer over a
+/// diffuse base, chosen by `metallic` and `transmission`.
+#[derive(Clone)]
+pub struct Principled {
+    base_color: Texture,
+    metallic: Texture,
+    roughness: Texture,
+    specular: Texture,
+    sheen: Texture,
+    clearcoat: Texture,
+    clearcoat_roughness: f64,
+    transmission: Texture,
+    index_of_refraction: f64,
+    emission: Texture,
+}
+
+impl Principled {
+    /// A rough, non-metallic, opaque material
+    pub fn new(base_color: impl Into<Texture>) -> Self {
+        Self {
+            base_color: base_color.into(),
+            metallic: 0.0.into(),
+            roughness: 0.5.into(),
+            specular: 0.5.into(),
+            sheen: DVec3::ZERO.into(),
+            clearcoat: 0.0.into(),
+            clearcoat_roughness: 0.03,
+            transmission: 0.0.into(),
+            index_of_refraction: 1.5,
+            emission: DVec3::ZERO.into(),
+        }
+    }
+    /// How much the surface behaves like a metal, which
+    /// tints its reflections with the base color and has
+    /// no diffuse part
+    pub fn with_metallic(
+        mut self,
+        metallic: impl Into<Texture>,
+    ) -> Self {
+        self.metallic = metallic.into();
+        self
+    }
+    /// From a mirror at 0 to very rough at 1
+    pub fn with_roughness(
+        mut self,
+        roughness: impl Into<Texture>,
+    ) -> Self {
+        self.roughness = roughness.into();
+        self
+    }
+    /// The strength of non-metallic reflections, where the
+    /// default of 0.5 is a reflectance of 4% head on, as
+    /// for most plastics and glass
+    pub fn with_specular(
+        mut self,
+        specular: impl Into<Texture>,
+    ) -> Self {
+        self.specular = specular.into();
+        self
+    }
+    /// The color of the soft extra reflection at grazing
+    /// angles that cloth has
+    pub fn with_sheen(
+        mut self,
+        sheen: impl Into<Texture>,
+    ) -> Self {
+        self.sheen = sheen.into();
+        self
+    }
+    /// How much of a smooth, colorless varnish covers the
+    /// surface
+    pub fn with_clearcoat(
+        mut self,
+        clearcoat: impl Into<Texture>,
+    ) -> Self {
+        self.clearcoat = clearcoat.into();
+        self
+    }
+    pub fn with_clearcoat_roughness(
+        mut self,
+        clearcoat_roughness: f64,
+    ) -> Self {
+        self.clearcoat_roughness = clearcoat_roughness;
+        self
+    }
+    /// How much light passes through the surface, as it
+    /// would through glass tinted by the base color
+    pub fn with_transmission(
+        mut self,
+        transmission: impl Into<Texture>,
+    ) -> Self {
+        self.transmission = transmission.into();
+        self
+    }
+    /// The index of refraction for transmitted light
+    pub fn with_index_of_refraction(
+        mut self,
+        index_of_refraction: f64,
+    ) -> Self {
+        self.index_of_refraction = index_of_refraction;
+        self
+    }
+    pub fn with_emission(
+        mut self,
+        emission: impl Into<Texture>,
+    ) -> Self {
+        self.emission = emission.into();
+        self
+    }
+
+    pub(super) fn emitted(
+        &self,
+        u: f64,
+        v: f64,
+        point: DVec3,
+    ) -> DVec3 {
+        self.emission.color(u, v, point)
+    }
+
+    pub(super) fn scatter(
+        &self,
+        r_in: &Ray,
+        hit_record: &HitRecord,
+    ) -> Option<Scattered> {
+        let mut rng = rand::thread_rng();
+        let scalar = |texture: &Texture| {
+            texture
+                .color(
+                    hit_record.u,
+                    hit_record.v,
+                    hit_record.point,
+                )
+                .x
+                .clamp(0., 1.)
+        };
+
+        let basis = Basis::new(hit_record.normal);
+        let wo = basis.local(-r_in.direction.normalize());
+        if wo.z <= 0. {
+            return None;
+        }
+        let alpha =
+            roughness_to_alpha(scalar(&self.roughness));
+
+        // Only transmission lets rays inside, so all they
+        // can do there is find their way out again.
+        if !hit_record.front_face {
+            let (wi, attenuation) = self.transmit(
+                wo,
+                alpha,
+                self.index_of_refraction,
+                DVec3::ONE,
+            )?;
+            return Some(scattered(
+                r_in,
+                hit_record,
+                basis.world(wi),
+                attenuation,
+            ));
+        }
+
+        let base_color = self.base_color.color(
+            hit_record.u,
+            hit_record.v,
+            hit_record.point,
+        );
+
+        // Each layer reflects light with the probability
+        // given by its Fresnel term and passes the rest on
+        // to the layer below, so the weights only need to
+        // carry color and the shadowing that the visible
+        // normal sampling doesn't account for.
+        let clearcoat_alpha =
+            roughness_to_alpha(self.clearcoat_roughness);
+        let h =
+            sample_microfacet_normal(wo, clearcoat_alpha);
+        if scalar(&self.clearcoat)
+            * fresnel_dielectric(
+                wo.dot(h),
+                CLEARCOAT_INDEX_OF_REFRACTION.recip(),
+            )
+            > rng.gen::<f64>()
+        {
+            let wi = reflect(-wo, h);
+            if wi.z <= 0. {
+                return None;
+            }
+            return Some(scattered(
+                r_in,
+                hit_record,
+                basis.world(wi),
+                DVec3::splat(shadowing_weight(
+                    wo,
+                    wi,
+                    clearcoat_alpha,
+                )),
+            ));
+        }
+
+        let h = sample_microfacet_normal(wo, alpha);
+        let (wi, attenuation) = if scalar(&self.metallic)
+            > rng.gen::<f64>()
+        {
+            let wi = reflect(-wo, h);
+            // absorb any scatter that is below the surface
+            if wi.z <= 0. {
+                return None;
+            }
+            (
+                wi,
+                fresnel_schlick(wo.dot(h), base_color)
+                    * shadowing_weight(wo, wi, alpha),
+            )
+        } else if scalar(&self.transmission)
+            > rng.gen::<f64>()
+        {
+            self.transmit(
+                wo,
+                alpha,
+                self.index_of_refraction.recip(),
+                base_color,
+            )?
+        } else {
+            // 0.08 maps the default specular of 0.5 to the 4%
+            // reflectance of an index of refraction of 1.5
+            let f0 = 0.08 * scalar(&self.specular);
+            let fresnel = fresnel_schlick(
+                wo.dot(h),
+                DVec3::splat(f0),
+            )
+            .x;
+            if fresnel > rng.gen::<f64>() {
+                let wi = reflect(-wo, h);
+                if wi.z <= 0. {
+                    return None;
+                }
+                (
+                    wi,
+                    DVec3::splat(shadowing_weight(
+                        wo, wi, alpha,
+                    )),
+                )
+            } else {
+                let wi = random_cosine_direction();
+                // Disney's sheen lobe, weighted for the cosine
+                // sampling of the diffuse lobe
+                let cos_d = wi.dot((wi + wo).normalize());
+                let sheen = self.sheen.color(
+                    hit_record.u,
+                    hit_record.v,
+                    hit_record.point,
+                ) * PI
+                    * (1. - cos_d).powi(5);
+                (wi, base_color + sheen)
+            }
+        };
+
+        Some(scattered(
+            r_in,
+            hit_record,
+            basis.world(wi),
+            attenuation,
+        ))
+    }
+
+    /// Reflect off of or refract through a rough glass
+    /// surface, tinting refracted light by `tint`
+    fn transmit(
+        &self,
+        wo: DVec3,
+        alpha: f64,
+        refraction_ratio: f64,
+        tint: DVec3,
+    ) -> Option<(DVec3, DVec3)> {
+        let (wi, weight) = sample_rough_interface(
+            wo,
+            refraction_ratio,
+            alpha,
+        )?;
+        Some(if wi.z > 0. {
+            (wi, DVec3::splat(weight))
+        } else {
+            (wi, tint * weight)
+        })
+    }
+}
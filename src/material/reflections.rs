@@ -28,3 +28,56 @@ pub fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
     r0 = r0 * r0;
     return r0 + (1. - r0) * (1. - cosine).powf(5.);
 }
+
+/// Exact Fresnel reflectance of a dielectric for unpolarized
+/// light, where `refraction_ratio` is the index of
+/// refraction being left over the one being entered.
+pub fn fresnel_dielectric(
+    cos_theta: f64,
+    refraction_ratio: f64,
+) -> f64 {
+    let sin2_t = refraction_ratio
+        * refraction_ratio
+        * (1. - cos_theta * cos_theta);
+    // total internal reflection
+    if sin2_t >= 1. {
+        return 1.;
+    }
+    let cos_t = (1. - sin2_t).sqrt();
+
+    let rs = (refraction_ratio * cos_theta - cos_t)
+        / (refraction_ratio * cos_theta + cos_t);
+    let rp = (cos_theta - refraction_ratio * cos_t)
+        / (cos_theta + refraction_ratio * cos_t);
+    0.5 * (rs * rs + rp * rp)
+}
+
+/// Exact Fresnel reflectance of a conductor with complex
+/// index of refraction `eta + i k`, per color channel.
+pub fn fresnel_conductor(
+    cos_theta: f64,
+    eta: DVec3,
+    k: DVec3,
+) -> DVec3 {
+    let cos2 = cos_theta * cos_theta;
+    let sin2 = 1. - cos2;
+
+    let t0 = eta * eta - k * k - sin2;
+    let a2b2 = (t0 * t0 + 4. * eta * eta * k * k).powf(0.5);
+    let t1 = a2b2 + cos2;
+    let a = (0.5 * (a2b2 + t0)).max(DVec3::ZERO).powf(0.5);
+    let t2 = 2. * cos_theta * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+/// Schlick's approximation of the Fresnel reflectance,
+/// starting from the reflectance `f0` at normal incidence
+pub fn fresnel_schlick(cos_theta: f64, f0: DVec3) -> DVec3 {
+    f0 + (1. - f0) * (1. - cos_theta).max(0.).powi(5)
+}
@@ -0,0 +1,145 @@
+use glam::DVec3;
+use rand::Rng;
+use std::{f64::consts::PI, sync::OnceLock};
+
+use super::{
+    microfacet::SMOOTH_ALPHA, reflections::reflect,
+};
+
+/// How many entries the table of sheen albedos has along
+/// each of its axes
+const ALBEDO_TABLE_SIZE: usize = 32;
+/// How many samples each entry of the table is integrated
+/// with, along each of the two sampling dimensions
+const ALBEDO_SAMPLES: usize = 64;
+
+/// The "Charlie" distribution of microfibers from Estevez
+/// and Kulla's "Production Friendly Microfacet Sheen
+/// BRDF", for a half vector at `cos_theta` to the normal.
+/// Unlike GGX, it is strongest at grazing angles, which
+/// gives cloth its soft rim of light.
+pub fn sheen_distribution(
+    cos_theta: f64,
+    alpha: f64,
+) -> f64 {
+    let sin_theta =
+        (1. - cos_theta * cos_theta).max(0.).sqrt();
+    (2. + alpha.recip()) * sin_theta.powf(alpha.recip())
+        / (2. * PI)
+}
+
+/// Sample a half vector in proportion to the sheen
+/// distribution times its cosine
+pub fn sample_sheen_normal(alpha: f64) -> DVec3 {
+    let mut rng = rand::thread_rng();
+    sheen_normal(rng.gen(), rng.gen(), alpha)
+}
+
+/// The half vector [`sample_sheen_normal`] picks for the
+/// random numbers `u1` and `u2`
+fn sheen_normal(u1: f64, u2: f64, alpha: f64) -> DVec3 {
+    let sin_theta = u1.powf(alpha / (2. * alpha + 1.));
+    let cos_theta = (1. - sin_theta * sin_theta).sqrt();
+    let phi = 2. * PI * u2;
+    DVec3::new(
+        sin_theta * phi.cos(),
+        sin_theta * phi.sin(),
+        cos_theta,
+    )
+}
+
+/// The probability density of sampling the local
+/// direction `wi` from `wo` by reflecting off of a half
+/// vector from [`sample_sheen_normal`]
+pub fn sheen_pdf(wo: DVec3, wi: DVec3, alpha: f64) -> f64 {
+    let h = (wo + wi).normalize();
+    sheen_distribution(h.z, alpha) * h.z
+        / (4. * wo.dot(h).abs())
+}
+
+/// The sheen BRDF with the visibility term from Neubelt
+/// and Pettineo's "Crafting a Next-Gen Material Pipeline
+/// for The Order: 1886"
+pub fn sheen(wo: DVec3, wi: DVec3, alpha: f64) -> f64 {
+    let h = (wo + wi).normalize();
+    sheen_distribution(h.z, alpha)
+        / (4. * (wi.z + wo.z - wi.z * wo.z))
+}
+
+/// The fraction of light arriving at `cos_theta` to the
+/// normal that the sheen reflects, which a base under it
+/// doesn't get. Looked up in a table over the square root
+/// of the cosine and the fourth root of `alpha`, which
+/// gives more entries to grazing light and sharp sheens
+/// where it changes fastest, and is integrated the first
+/// time it is needed.
+pub fn sheen_albedo(cos_theta: f64, alpha: f64) -> f64 {
+    static TABLE: OnceLock<Vec<f64>> = OnceLock::new();
+    let last = (ALBEDO_TABLE_SIZE - 1) as f64;
+    let table = TABLE.get_or_init(|| {
+        (0..ALBEDO_TABLE_SIZE * ALBEDO_TABLE_SIZE)
+            .map(|i| {
+                let root_alpha =
+                    ((i / ALBEDO_TABLE_SIZE) as f64 / last)
+                        .powi(2);
+                let root_cos =
+                    (i % ALBEDO_TABLE_SIZE) as f64 / last;
+                integrate_sheen_albedo(
+                    root_cos * root_cos,
+                    root_alpha * root_alpha,
+                )
+            })
+            .collect()
+    });
+
+    // interpolate between the four nearest entries
+    let x = cos_theta.clamp(0., 1.).sqrt() * last;
+    let y = alpha.clamp(0., 1.).powf(0.25) * last;
+    let (x0, y0) = (
+        (x as usize).min(ALBEDO_TABLE_SIZE - 2),
+        (y as usize).min(ALBEDO_TABLE_SIZE - 2),
+    );
+    let (tx, ty) = (x - x0 as f64, y - y0 as f64);
+    let entry = |x: usize, y: usize| {
+        table[y * ALBEDO_TABLE_SIZE + x]
+    };
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+    lerp(
+        lerp(entry(x0, y0), entry(x0 + 1, y0), tx),
+        lerp(entry(x0, y0 + 1), entry(x0 + 1, y0 + 1), tx),
+        ty,
+    )
+}
+
+/// Integrate the sheen over the hemisphere for light
+/// arriving at `cos_theta`, with stratified samples from
+/// [`sample_sheen_normal`]. The distribution cancels out
+/// of each sample's weight, which keeps sharp sheens from
+/// making the estimate noisy.
+fn integrate_sheen_albedo(
+    cos_theta: f64,
+    alpha: f64,
+) -> f64 {
+    let alpha = alpha.max(SMOOTH_ALPHA);
+    let wo = DVec3::new(
+        (1. - cos_theta * cos_theta).sqrt(),
+        0.,
+        cos_theta,
+    );
+    let mut sum = 0.;
+    for i in 0..ALBEDO_SAMPLES {
+        for j in 0..ALBEDO_SAMPLES {
+            let h = sheen_normal(
+                (i as f64 + 0.5) / ALBEDO_SAMPLES as f64,
+                (j as f64 + 0.5) / ALBEDO_SAMPLES as f64,
+                alpha,
+            );
+            let wi = reflect(-wo, h);
+            if wi.z > 0. {
+                sum += wi.z * wo.dot(h).abs()
+                    / (h.z * (wi.z + wo.z - wi.z * wo.z));
+            }
+        }
+    }
+    sum / (ALBEDO_SAMPLES * ALBEDO_SAMPLES) as f64
+}
@@ -0,0 +1,163 @@
+use std::{f64::consts::PI, ops::Range};
+
+use glam::DVec3;
+use rand::Rng;
+
+use crate::{
+    hittable::HitRecord, spectrum::reflectance_to_rgb,
+    textures::Texture,
+};
+
+/// A film a few hundred nanometers thick on a surface,
+/// like soap or oil. Light reflecting off of its top and
+/// bottom interferes, so how much is reflected depends on
+/// wavelength, which colors the reflection.
+#[derive(Clone)]
+pub struct ThinFilm {
+    thickness: Texture,
+    thickness_range: Range<f64>,
+    index_of_refraction: f64,
+}
+
+impl ThinFilm {
+    /// A film of even `thickness`, in nanometers
+    pub fn new(
+        thickness: f64,
+        index_of_refraction: f64,
+    ) -> Self {
+        Self {
+            thickness: 1.0.into(),
+            thickness_range: thickness..thickness,
+            index_of_refraction,
+        }
+    }
+    /// Vary the film's thickness across the surface, from
+    /// the start of `range` where the texture's red channel
+    /// is 0 to the end where it is 1, and beyond for other
+    /// values
+    pub fn with_thickness_texture(
+        mut self,
+        texture: Texture,
+        range: Range<f64>,
+    ) -> Self {
+        self.thickness = texture;
+        self.thickness_range = range;
+        self
+    }
+
+    /// How much light is reflected off of the film and the
+    /// surface under it at the point hit, for light
+    /// arriving at `cos_theta` to the normal through a
+    /// medium with index of refraction `outside` onto a
+    /// surface with index `inside`. Gives the reflectance
+    /// at `wavelength` in nanometers for every channel if
+    /// there is one, or the color of the reflection of
+    /// white light otherwise.
+    pub fn reflectance(
+        &self,
+        cos_theta: f64,
+        outside: f64,
+        inside: f64,
+        wavelength: Option<f64>,
+        hit_record: &HitRecord,
+    ) -> DVec3 {
+        let t = self
+            .thickness
+            .color(
+                hit_record.u,
+                hit_record.v,
+                hit_record.point,
+            )
+            .x;
+        let thickness = (self.thickness_range.start
+            + t * (self.thickness_range.end
+                - self.thickness_range.start))
+            .max(0.);
+        let reflectance = |wavelength: f64| {
+            airy_reflectance(
+                cos_theta,
+                [outside, self.index_of_refraction, inside],
+                thickness,
+                wavelength,
+            )
+        };
+        match wavelength {
+            Some(wavelength) => {
+                DVec3::splat(reflectance(wavelength))
+            }
+            None => reflectance_to_rgb(reflectance),
+        }
+    }
+    /// Pick whether light reflects off of the film or
+    /// passes through it, for the same arguments as
+    /// [`ThinFilm::reflectance`]. The film reflects each
+    /// color differently, so this picks by the average and
+    /// weights each color to make up for it. Gives whether
+    /// the light reflected and its weight.
+    pub fn choose_reflection(
+        &self,
+        cos_theta: f64,
+        outside: f64,
+        inside: f64,
+        wavelength: Option<f64>,
+        hit_record: &HitRecord,
+    ) -> (bool, DVec3) {
+        let reflectance = self.reflectance(
+            cos_theta, outside, inside, wavelength,
+            hit_record,
+        );
+        let probability =
+            (reflectance.x + reflectance.y + reflectance.z)
+                / 3.;
+        if probability > rand::thread_rng().gen::<f64>() {
+            (true, reflectance / probability)
+        } else {
+            (false, (1. - reflectance) / (1. - probability))
+        }
+    }
+}
+
+/// The reflectance of a single film for unpolarized
+/// light, summing every reflection inside the film with
+/// its phase. `n` are the indices of refraction of the
+/// outside, the film and the inside, in that order.
+fn airy_reflectance(
+    cos_theta: f64,
+    n: [f64; 3],
+    thickness: f64,
+    wavelength: f64,
+) -> f64 {
+    let sin2 = 1. - cos_theta * cos_theta;
+    // the cosine of the angle in each of the other media,
+    // by Snell's law
+    let cos_in = |index: f64| {
+        let sin2 = (n[0] / index).powi(2) * sin2;
+        (sin2 < 1.).then(|| (1. - sin2).sqrt())
+    };
+    // Total internal reflection at either interface sends
+    // all of the light back, ignoring the little that
+    // tunnels through a thin enough film.
+    let (Some(cos_film), Some(cos_inside)) =
+        (cos_in(n[1]), cos_in(n[2]))
+    else {
+        return 1.;
+    };
+    let cos = [cos_theta, cos_film, cos_inside];
+
+    let phase =
+        4. * PI * n[1] * thickness * cos_film / wavelength;
+    let airy = |r12: f64, r23: f64| {
+        let cross = 2. * r12 * r23 * phase.cos();
+        (r12 * r12 + r23 * r23 + cross)
+            / (1. + r12 * r12 * r23 * r23 + cross)
+    };
+    let s = |i: usize, j: usize| {
+        (n[i] * cos[i] - n[j] * cos[j])
+            / (n[i] * cos[i] + n[j] * cos[j])
+    };
+    let p = |i: usize, j: usize| {
+        (n[j] * cos[i] - n[i] * cos[j])
+            / (n[j] * cos[i] + n[i] * cos[j])
+    };
+    0.5 * (airy(s(0, 1), s(1, 2)) + airy(p(0, 1), p(1, 2)))
+}
@@ -20,6 +20,18 @@ pub fn random_unit_vector() -> DVec3 {
     return random_in_unit_sphere().normalize();
 }
 
+/// A direction on the hemisphere around +z, with a
+/// probability proportional to its cosine with z
+pub fn random_cosine_direction() -> DVec3 {
+    let direction = DVec3::Z + random_unit_vector();
+    // Catch degenerate scatter direction
+    if direction.abs_diff_eq(DVec3::ZERO, 1e-8) {
+        DVec3::Z
+    } else {
+        direction.normalize()
+    }
+}
+
 // this function is used in the series, but is unused by the end
 // of the series
 // fn random_on_hemisphere(normal: &DVec3) -> DVec3 {
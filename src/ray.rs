@@ -1,7 +1,12 @@
-use glam::DVec3;
+use glam::{DVec3, DVec4};
 
-use crate::{hittable::Hittable, material::Scattered};
+use crate::{
+    hittable::Hittable,
+    material::{Scattered, Walk},
+    spectrum::{rgb_to_spectrum, Wavelengths},
+};
 
+#[derive(Clone)]
 pub struct Ray {
     pub origin: DVec3,
     pub direction: DVec3,
@@ -18,6 +23,34 @@ impl Default for Ray {
     }
 }
 
+/// Counters for the work done while tracing rays
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RayStats {
+    /// Rays shot from the camera
+    pub primary_rays: u64,
+    /// Every ray segment traced, primary rays included
+    pub rays: u64,
+    /// Ray segments that hit something in the world
+    pub intersections: u64,
+    /// Nodes of a [`Bvh`](crate::bvh::Bvh) whose boxes
+    /// were tested against a ray
+    pub bvh_nodes: u64,
+    /// Rays cast towards a light to test whether it is
+    /// visible. These stay at 0 until lights are sampled
+    /// directly.
+    pub shadow_rays: u64,
+}
+
+impl std::ops::AddAssign for RayStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.primary_rays += rhs.primary_rays;
+        self.rays += rhs.rays;
+        self.intersections += rhs.intersections;
+        self.bvh_nodes += rhs.bvh_nodes;
+        self.shadow_rays += rhs.shadow_rays;
+    }
+}
+
 impl Ray {
     pub fn at(&self, t: f64) -> DVec3 {
         self.origin + t * self.direction
@@ -28,6 +61,25 @@ impl Ray {
         world: &T,
         miss_color: &Option<DVec3>,
     ) -> DVec3
+    where
+        T: Hittable + std::marker::Sync,
+    {
+        self.color_with_stats(
+            depth,
+            world,
+            miss_color,
+            &mut RayStats::default(),
+        )
+    }
+    /// The same as [`Ray::color`], but counts every
+    /// segment of the path in `stats`.
+    pub fn color_with_stats<T>(
+        &self,
+        depth: u32,
+        world: &T,
+        miss_color: &Option<DVec3>,
+        stats: &mut RayStats,
+    ) -> DVec3
     where
         T: Hittable + std::marker::Sync,
     {
@@ -35,10 +87,28 @@ impl Ray {
         if depth <= 0 {
             return DVec3::ZERO;
         }
+        stats.rays += 1;
         // if we hit something
-        if let Some(rec) =
-            world.hit(&self, (0.001)..f64::INFINITY)
-        {
+        if let Some(rec) = world.hit_with_stats(
+            &self,
+            (0.001)..f64::INFINITY,
+            stats,
+        ) {
+            stats.intersections += 1;
+            // rays inside a translucent material can
+            // scatter before they reach its surface
+            let Walk {
+                ray,
+                hit_record: rec,
+                attenuation: walk_attenuation,
+            } = rec
+                .material
+                .scatter_inside(self, &rec, world, stats)
+                .unwrap_or(Walk {
+                    ray: self.clone(),
+                    hit_record: rec,
+                    attenuation: DVec3::ONE,
+                });
             let color_from_emission = rec
                 .material
                 .emitted(rec.u, rec.v, rec.point);
@@ -49,22 +119,29 @@ impl Ray {
             let Some(Scattered {
                 attenuation,
                 scattered,
-            }) = rec.material.scatter(self, &rec)
+            }) = rec.material.scatter(&ray, &rec)
             else {
-                return color_from_emission;
+                return walk_attenuation
+                    * color_from_emission;
             };
 
             // recurse to follow more bounces
             let color_from_scatter = attenuation
-                * scattered.color(
+                * scattered.color_with_stats(
                     depth - 1,
                     world,
                     miss_color,
+                    stats,
                 );
-            return color_from_emission
-                + color_from_scatter;
+            return walk_attenuation
+                * (color_from_emission
+                    + color_from_scatter);
         }
 
+        self.miss(miss_color)
+    }
+    /// The color of a ray that hit nothing
+    fn miss(&self, miss_color: &Option<DVec3>) -> DVec3 {
         miss_color.unwrap_or_else(|| {
             // this is sky because we missed everything
             let a =
@@ -73,4 +150,84 @@ impl Ray {
                 + a * DVec3::new(0.5, 0.7, 1.0);
         })
     }
+    /// The same as [`Ray::color_with_stats`], but follows
+    /// the path at each of `wavelengths` instead of in RGB,
+    /// returning the radiance at each of them. Colors in
+    /// the scene are turned into spectra as the path meets
+    /// them.
+    pub fn spectral_color<T>(
+        &self,
+        depth: u32,
+        world: &T,
+        miss_color: &Option<DVec3>,
+        wavelengths: &mut Wavelengths,
+        stats: &mut RayStats,
+    ) -> DVec4
+    where
+        T: Hittable + std::marker::Sync,
+    {
+        if depth == 0 {
+            return DVec4::ZERO;
+        }
+        stats.rays += 1;
+        let Some(rec) = world.hit_with_stats(
+            self,
+            (0.001)..f64::INFINITY,
+            stats,
+        ) else {
+            return rgb_to_spectrum(
+                self.miss(miss_color),
+                wavelengths,
+            );
+        };
+        stats.intersections += 1;
+        let Walk {
+            ray,
+            hit_record: rec,
+            attenuation: walk_attenuation,
+        } = rec
+            .material
+            .scatter_inside(self, &rec, world, stats)
+            .unwrap_or(Walk {
+                ray: self.clone(),
+                hit_record: rec,
+                attenuation: DVec3::ONE,
+            });
+        let walk_attenuation =
+            rgb_to_spectrum(walk_attenuation, wavelengths);
+
+        let emission = rgb_to_spectrum(
+            rec.material.emitted(rec.u, rec.v, rec.point),
+            wavelengths,
+        );
+        let Some(Scattered {
+            attenuation,
+            scattered,
+        }) = rec.material.scatter_with_wavelength(
+            &ray,
+            &rec,
+            Some(wavelengths.hero()),
+        )
+        else {
+            return walk_attenuation * emission;
+        };
+        // the scattered direction is only right for the
+        // hero wavelength
+        if rec.material.is_dispersive() {
+            wavelengths.terminate_secondary();
+        }
+
+        let attenuation =
+            rgb_to_spectrum(attenuation, wavelengths);
+        walk_attenuation
+            * (emission
+                + attenuation
+                    * scattered.spectral_color(
+                        depth - 1,
+                        world,
+                        miss_color,
+                        wavelengths,
+                        stats,
+                    ))
+    }
 }
@@ -0,0 +1,9 @@
+use rand::Rng;
+
+/// How far light travels through a medium before it
+/// scatters, which is exponentially distributed around
+/// the `mean_free_path`
+pub fn sample_free_flight(mean_free_path: f64) -> f64 {
+    -mean_free_path
+        * (1. - rand::thread_rng().gen::<f64>()).ln()
+}
@@ -1,16 +1,30 @@
 use glam::DVec3;
 
-use crate::{hittable::Hittable, ray::Ray};
+use crate::{aabb::Aabb, hittable::Hittable, ray::Ray};
 
+use self::csg::Operation;
+
+pub mod a_box;
+pub mod animated_transform;
+pub mod capsule;
+pub mod cone;
 pub mod constant_medium;
+pub mod csg;
+pub mod cylinder;
+pub mod disk;
+mod frame;
+pub mod heightfield;
+pub mod heterogeneous_medium;
+pub mod paraboloid;
+pub mod plane;
+mod polynomial;
 pub mod quad;
 pub mod quad_box;
+pub mod rounded_box;
+pub mod sdf;
 pub mod sphere;
-// are other shapes useful?
-// possible SDF definitions?
-// pub mod a_box;
-// pub mod cylinder;
-// pub mod rounded_box;
+pub mod torus;
+pub mod transform;
 
 pub enum Shapes {
     Sphere(sphere::Sphere),
@@ -26,10 +40,30 @@ pub enum Shapes {
         object: Box<Shapes>,
     },
     ConstantMedium(constant_medium::ConstantMedium),
+    Transform(transform::Transform),
+    AnimatedTransform(
+        animated_transform::AnimatedTransform,
+    ),
     Collection(Vec<Shapes>),
-    // RoundedBox(rounded_box::RoundedBox),
-    // Box(a_box::Box),
-    // Cylinder(cylinder::Cylinder),
+    RoundedBox(rounded_box::RoundedBox),
+    Box(a_box::Box),
+    Cylinder(cylinder::Cylinder),
+    Disk(disk::Disk),
+    Annulus(disk::Annulus),
+    Torus(torus::Torus),
+    Cone(cone::Cone),
+    Capsule(capsule::Capsule),
+    Paraboloid(paraboloid::Paraboloid),
+    Plane(plane::Plane),
+    Sdf(sdf::Sdf),
+    Heightfield(heightfield::Heightfield),
+    HeterogeneousMedium(
+        heterogeneous_medium::HeterogeneousMedium,
+    ),
+    Union(Box<Shapes>, Box<Shapes>),
+    Intersection(Box<Shapes>, Box<Shapes>),
+    /// The first shape with the second one cut out of it
+    Difference(Box<Shapes>, Box<Shapes>),
 }
 impl Shapes {
     pub fn new_rotate_y(
@@ -45,6 +79,19 @@ impl Shapes {
             object: Box::new(object),
         }
     }
+    /// Everything inside either shape. Like the other CSG
+    /// nodes, both shapes have to be closed.
+    pub fn new_union(a: Shapes, b: Shapes) -> Self {
+        Self::Union(Box::new(a), Box::new(b))
+    }
+    /// Everything inside both shapes
+    pub fn new_intersection(a: Shapes, b: Shapes) -> Self {
+        Self::Intersection(Box::new(a), Box::new(b))
+    }
+    /// Everything inside `a` but not inside `b`
+    pub fn new_difference(a: Shapes, b: Shapes) -> Self {
+        Self::Difference(Box::new(a), Box::new(b))
+    }
 }
 
 impl Hittable for Shapes {
@@ -126,17 +173,179 @@ impl Hittable for Shapes {
                 normal.z = -sin_theta * hit_record.normal.x
                     + cos_theta * hit_record.normal.z;
 
+                let mut tangent = hit_record.tangent;
+                tangent.x = cos_theta
+                    * hit_record.tangent.x
+                    + sin_theta * hit_record.tangent.z;
+                tangent.z = -sin_theta
+                    * hit_record.tangent.x
+                    + cos_theta * hit_record.tangent.z;
+
                 hit_record.point = p;
                 hit_record.normal = normal;
 
-                Some(hit_record)
+                Some(hit_record.with_tangent(tangent))
             }
             Shapes::ConstantMedium(object) => {
                 object.hit(ray, interval)
             }
+            Shapes::Transform(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::AnimatedTransform(object) => {
+                object.hit(ray, interval)
+            }
             Shapes::Collection(object) => {
                 object.hit(ray, interval)
             }
+            Shapes::RoundedBox(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Box(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Cylinder(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Disk(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Annulus(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Torus(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Cone(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Capsule(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Paraboloid(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Plane(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Sdf(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Heightfield(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::HeterogeneousMedium(object) => {
+                object.hit(ray, interval)
+            }
+            Shapes::Union(a, b) => csg::hit(
+                Operation::Union,
+                a,
+                b,
+                ray,
+                interval,
+            ),
+            Shapes::Intersection(a, b) => csg::hit(
+                Operation::Intersection,
+                a,
+                b,
+                ray,
+                interval,
+            ),
+            Shapes::Difference(a, b) => csg::hit(
+                Operation::Difference,
+                a,
+                b,
+                ray,
+                interval,
+            ),
+        }
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        match self {
+            Shapes::Sphere(object) => object.bounding_box(),
+            Shapes::Quad(object) => object.bounding_box(),
+            Shapes::QuadBox(object) => {
+                object.bounding_box()
+            }
+            Shapes::Translate { offset, object } => {
+                let aabb = object.bounding_box()?;
+                Some(Aabb {
+                    min: aabb.min + *offset,
+                    max: aabb.max + *offset,
+                })
+            }
+            Shapes::RotateY {
+                sin_theta,
+                cos_theta,
+                object,
+            } => {
+                let aabb = object.bounding_box()?;
+                Some(Aabb::from_points(aabb.corners().map(
+                    |corner| {
+                        DVec3::new(
+                            cos_theta * corner.x
+                                + sin_theta * corner.z,
+                            corner.y,
+                            -sin_theta * corner.x
+                                + cos_theta * corner.z,
+                        )
+                    },
+                )))
+            }
+            Shapes::ConstantMedium(object) => {
+                object.bounding_box()
+            }
+            Shapes::Transform(object) => {
+                object.bounding_box()
+            }
+            Shapes::AnimatedTransform(object) => {
+                object.bounding_box()
+            }
+            Shapes::Collection(object) => {
+                object.bounding_box()
+            }
+            Shapes::RoundedBox(object) => {
+                object.bounding_box()
+            }
+            Shapes::Box(object) => object.bounding_box(),
+            Shapes::Cylinder(object) => {
+                object.bounding_box()
+            }
+            Shapes::Disk(object) => object.bounding_box(),
+            Shapes::Annulus(object) => {
+                object.bounding_box()
+            }
+            Shapes::Torus(object) => object.bounding_box(),
+            Shapes::Cone(object) => object.bounding_box(),
+            Shapes::Capsule(object) => {
+                object.bounding_box()
+            }
+            Shapes::Paraboloid(object) => {
+                object.bounding_box()
+            }
+            Shapes::Plane(object) => object.bounding_box(),
+            Shapes::Sdf(object) => object.bounding_box(),
+            Shapes::Heightfield(object) => {
+                object.bounding_box()
+            }
+            Shapes::HeterogeneousMedium(object) => {
+                object.bounding_box()
+            }
+            Shapes::Union(a, b) => {
+                csg::bounding_box(Operation::Union, a, b)
+            }
+            Shapes::Intersection(a, b) => {
+                csg::bounding_box(
+                    Operation::Intersection,
+                    a,
+                    b,
+                )
+            }
+            Shapes::Difference(a, b) => csg::bounding_box(
+                Operation::Difference,
+                a,
+                b,
+            ),
         }
     }
 }
@@ -1,17 +1,52 @@
 use std::ops::Range;
 
-use glam::{DVec3, Vec3Swizzles};
+use glam::{DQuat, DVec3, Vec3Swizzles};
 
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     material::Material,
     ray::Ray,
 };
 
+/// A box intersected analytically as a single shape,
+/// rather than as six quads like
+/// [`QuadBox`](super::quad_box::QuadBox).
 pub struct Box {
-    pub center: DVec3,
-    pub size: DVec3,
-    pub material: Material,
+    center: DVec3,
+    /// half of the box's extent along each local axis
+    size: DVec3,
+    rotation: DQuat,
+    material: Material,
+}
+
+impl Box {
+    /// `size` is half of the box's extent along each axis
+    pub fn new(
+        center: DVec3,
+        size: DVec3,
+        material: Material,
+    ) -> Self {
+        Self {
+            center,
+            size,
+            rotation: DQuat::IDENTITY,
+            material,
+        }
+    }
+    /// Rotate the box by `angle` degrees around an `axis`
+    /// through its center.
+    pub fn with_rotation(
+        mut self,
+        axis: DVec3,
+        angle: f64,
+    ) -> Self {
+        self.rotation = DQuat::from_axis_angle(
+            axis.normalize(),
+            angle.to_radians(),
+        );
+        self
+    }
 }
 
 impl Hittable for Box {
@@ -20,59 +55,72 @@ impl Hittable for Box {
         ray: &Ray,
         interval: Range<f64>,
     ) -> Option<HitRecord> {
-        let m: DVec3 = ray.direction.signum()
-            / ray.direction.abs().max(DVec3::splat(1e-8));
-        let n: DVec3 = m * ray.origin;
+        // intersect in the box's local space, where it is
+        // centered on the origin and axis-aligned
+        let to_local = self.rotation.inverse();
+        let ro = to_local * (ray.origin - self.center);
+        let rd = to_local * ray.direction;
+
+        let m: DVec3 =
+            rd.signum() / rd.abs().max(DVec3::splat(1e-8));
+        let n: DVec3 = m * ro;
         let k: DVec3 = m.abs() * self.size;
 
         let t1: DVec3 = -n - k;
         let t2: DVec3 = -n + k;
 
-        let tN: f64 = t1.x.max(t1.y).max(t1.z);
-        let tF: f64 = t2.x.min(t2.y).min(t2.z);
+        let t_near: f64 = t1.x.max(t1.y).max(t1.z);
+        let t_far: f64 = t2.x.min(t2.y).min(t2.z);
 
-        if tN > tF || tF <= 0. {
+        if t_near > t_far {
             return None;
-        } else {
-            if interval.contains(&tN) {
-                let normal = -ray.direction.signum()
-                    * step(t1.yzx(), t1.xyz())
-                    * step(t1.zxy(), t1.xyz());
-                // return tN;
-                return Some(HitRecord::with_face_normal(
-                    self.material.clone(),
-                    ray.at(tN),
-                    normal,
-                    tN,
-                    ray,
-                ));
-            } else if interval.contains(&tF) {
-                let normal = -ray.direction.signum()
-                    * step(t1.yzx(), t1.xyz())
-                    * step(t1.zxy(), t1.xyz());
-                // return tF;
-                return Some(HitRecord::with_face_normal(
-                    self.material.clone(),
-                    ray.at(tF),
-                    normal,
-                    tF,
-                    ray,
-                ));
+        }
+        let (t, local_normal) =
+            if interval.contains(&t_near) {
+                // entering through the face whose slab we
+                // crossed last
+                (
+                    t_near,
+                    -rd.signum()
+                        * step(t1.yzx(), t1.xyz())
+                        * step(t1.zxy(), t1.xyz()),
+                )
+            } else if interval.contains(&t_far) {
+                // leaving through the face whose slab we
+                // cross first
+                (
+                    t_far,
+                    rd.signum()
+                        * step(t2.xyz(), t2.yzx())
+                        * step(t2.xyz(), t2.zxy()),
+                )
             } else {
                 return None;
-            }
-        }
-        // }
+            };
 
-        // let rec = HitRecord::with_face_normal(
-        //     self.material.clone(),
-        //     point,
-        //     outward_normal,
-        //     t,
-        //     ray,
-        // );
-
-        // Some(rec)
+        let local_point = ro + t * rd;
+        let (u, v) =
+            face_uv(local_point / self.size, local_normal);
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                ray.at(t),
+                self.rotation * local_normal,
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(
+                self.rotation * face_tangent(local_normal),
+            ),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let local = Aabb::new(-self.size, self.size);
+        Some(Aabb::from_points(local.corners().map(
+            |corner| self.center + self.rotation * corner,
+        )))
     }
 }
 
@@ -84,3 +132,38 @@ fn step(edge: DVec3, x: DVec3) -> DVec3 {
         if bvec.z { 1.0 } else { 0.0 },
     )
 }
+
+/// Map a point on a box face to (u, v) in [0,1], using
+/// the two axes that lie in the face. `point` is scaled so
+/// the box spans -1 to 1 on every axis, and `normal` picks
+/// the face by its largest component.
+pub(super) fn face_uv(
+    point: DVec3,
+    normal: DVec3,
+) -> (f64, f64) {
+    let n = normal.abs();
+    let (a, b) = if n.x >= n.y && n.x >= n.z {
+        (point.z * -normal.x.signum(), point.y)
+    } else if n.y >= n.z {
+        (point.x, point.z * -normal.y.signum())
+    } else {
+        (point.x * normal.z.signum(), point.y)
+    };
+    (
+        (0.5 + 0.5 * a).clamp(0., 1.),
+        (0.5 + 0.5 * b).clamp(0., 1.),
+    )
+}
+
+/// The direction u increases in on the box face picked
+/// by `normal`, matching [`face_uv`]
+pub(super) fn face_tangent(normal: DVec3) -> DVec3 {
+    let n = normal.abs();
+    if n.x >= n.y && n.x >= n.z {
+        DVec3::new(0., 0., -normal.x.signum())
+    } else if n.y >= n.z {
+        DVec3::X
+    } else {
+        DVec3::new(normal.z.signum(), 0., 0.)
+    }
+}
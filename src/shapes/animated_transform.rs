@@ -0,0 +1,240 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    ray::Ray,
+};
+use glam::{DMat3, DMat4, DQuat, DVec3};
+use std::{ops::Range, sync::Arc};
+
+use super::{transform::hit_transformed, Shapes};
+
+/// How many poses are sampled across the animation when
+/// building its bounding box. Rotations can sweep outside
+/// of the boxes at the keyframes, so the keyframes alone
+/// are not enough, and the box is padded for the motion
+/// between samples.
+const BOUNDING_BOX_STEPS: usize = 64;
+
+/// The pose of an [`AnimatedTransform`] at a point in
+/// time. Scale is applied first, then rotation, then
+/// translation.
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: DVec3,
+    pub rotation: DQuat,
+    pub scale: DVec3,
+}
+
+impl Keyframe {
+    /// A keyframe that leaves the shape where it is
+    pub fn new(time: f64) -> Self {
+        Self {
+            time,
+            translation: DVec3::ZERO,
+            rotation: DQuat::IDENTITY,
+            scale: DVec3::ONE,
+        }
+    }
+    pub fn with_translation(
+        mut self,
+        translation: DVec3,
+    ) -> Self {
+        self.translation = translation;
+        self
+    }
+    /// Rotate by `angle` degrees around `axis`.
+    ///
+    /// The shape turns the shortest way from one
+    /// keyframe's rotation to the next, so turning by more
+    /// than 180° needs keyframes in between.
+    pub fn with_rotation(
+        mut self,
+        axis: DVec3,
+        angle: f64,
+    ) -> Self {
+        self.rotation = DQuat::from_axis_angle(
+            axis.normalize(),
+            angle.to_radians(),
+        );
+        self
+    }
+    pub fn with_scale(mut self, scale: DVec3) -> Self {
+        self.scale = scale;
+        self
+    }
+    /// Blend towards `other` by `t` in 0..=1, using
+    /// slerp for the rotation.
+    fn interpolate(
+        &self,
+        other: &Keyframe,
+        t: f64,
+    ) -> Self {
+        Self {
+            time: self.time + (other.time - self.time) * t,
+            translation: self
+                .translation
+                .lerp(other.translation, t),
+            rotation: self
+                .rotation
+                .slerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+    fn matrix(&self) -> DMat4 {
+        DMat4::from_scale_rotation_translation(
+            self.scale,
+            self.rotation,
+            self.translation,
+        )
+    }
+}
+
+/// Moves any shape along a set of keyframes, using the
+/// ray's time to pick the pose. This gives motion blur to
+/// shapes that can't move on their own.
+///
+/// Before the first keyframe and after the last one the
+/// shape holds still. Between keyframes the rotation takes
+/// the shortest way around, so a spin of more than 180°
+/// has to be split over several keyframes.
+pub struct AnimatedTransform {
+    keyframes: Vec<Keyframe>,
+    object: Arc<Shapes>,
+}
+
+impl AnimatedTransform {
+    /// # Panics
+    ///
+    /// if `keyframes` is empty
+    pub fn new(
+        keyframes: Vec<Keyframe>,
+        object: impl Into<Arc<Shapes>>,
+    ) -> Self {
+        assert!(
+            !keyframes.is_empty(),
+            "an animated transform needs at least one keyframe"
+        );
+        let mut keyframes = keyframes;
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            keyframes,
+            object: object.into(),
+        }
+    }
+    /// The interpolated pose at `time`
+    pub fn keyframe_at(&self, time: f64) -> Keyframe {
+        let next =
+            self.keyframes.partition_point(|keyframe| {
+                keyframe.time <= time
+            });
+        match next {
+            0 => self.keyframes[0],
+            next if next == self.keyframes.len() => {
+                self.keyframes[next - 1]
+            }
+            next => {
+                let from = &self.keyframes[next - 1];
+                let to = &self.keyframes[next];
+                from.interpolate(
+                    to,
+                    (time - from.time)
+                        / (to.time - from.time),
+                )
+            }
+        }
+    }
+}
+
+impl Hittable for AnimatedTransform {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let object_to_world =
+            self.keyframe_at(ray.time).matrix();
+        let world_to_object = object_to_world.inverse();
+        let normal_to_world =
+            DMat3::from_mat4(world_to_object).transpose();
+
+        hit_transformed(
+            &self.object,
+            &world_to_object,
+            &object_to_world,
+            &normal_to_world,
+            ray,
+            interval,
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let aabb = self.object.bounding_box()?;
+        let start = self.keyframes[0].time;
+        let end =
+            self.keyframes[self.keyframes.len() - 1].time;
+
+        // sample at the keyframes too, so that no step
+        // between samples crosses one
+        let mut times = (0..=BOUNDING_BOX_STEPS)
+            .map(|step| {
+                start
+                    + (end - start) * step as f64
+                        / BOUNDING_BOX_STEPS as f64
+            })
+            .chain(
+                self.keyframes
+                    .iter()
+                    .map(|keyframe| keyframe.time),
+            )
+            .collect::<Vec<f64>>();
+        times.sort_by(f64::total_cmp);
+        let poses = times
+            .into_iter()
+            .map(|time| self.keyframe_at(time))
+            .collect::<Vec<Keyframe>>();
+
+        let corners = aabb.corners();
+        let boxes = poses
+            .iter()
+            .map(|pose| aabb.transform(&pose.matrix()));
+        let steps = poses.windows(2).map(|pair| {
+            let [from, to] = pair else { unreachable!() };
+            let step = aabb
+                .transform(&from.matrix())
+                .union(&aabb.transform(&to.matrix()));
+            // Within a step every point turns on a circle
+            // around the rotation axis, so it strays from
+            // the straight line between its two positions
+            // by at most the circle's sagitta. Scale and
+            // translation change alongside the rotation,
+            // which can take it up to their change further.
+            let radius = corners
+                .iter()
+                .map(|corner| {
+                    (from.scale * *corner)
+                        .length()
+                        .max((to.scale * *corner).length())
+                })
+                .fold(0., f64::max);
+            let angle =
+                from.rotation.angle_between(to.rotation);
+            let scale_change = corners
+                .iter()
+                .map(|corner| {
+                    ((to.scale - from.scale) * *corner)
+                        .length()
+                })
+                .fold(0., f64::max);
+            let padding = radius
+                * (1. - (angle / 2.).cos())
+                + 2. * scale_change
+                + (to.translation - from.translation)
+                    .length();
+            Aabb {
+                min: step.min - padding,
+                max: step.max + padding,
+            }
+        });
+        boxes.chain(steps).reduce(|a, b| a.union(&b))
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::DVec3;
+use std::ops::Range;
+
+use super::{
+    frame::{angle_around_axis, Frame},
+    polynomial::solve_quadratic,
+};
+
+/// A cylinder from `start` to `end` with a hemisphere on
+/// each end: every point within `radius` of the segment.
+///
+/// u goes around the axis and v runs along the whole
+/// length, from the tip of one hemisphere to the other.
+pub struct Capsule {
+    frame: Frame,
+    height: f64,
+    radius: f64,
+    material: Material,
+}
+
+impl Capsule {
+    pub fn new(
+        start: DVec3,
+        end: DVec3,
+        radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            frame: Frame::new(start, end - start),
+            height: (end - start).length(),
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Capsule {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let o = self.frame.local_point(ray.origin);
+        let d = self.frame.local_vector(ray.direction);
+        let r2 = self.radius * self.radius;
+
+        // side: x² + z² = r², between the two ends
+        let side = solve_quadratic(
+            d.x * d.x + d.z * d.z,
+            2. * (o.x * d.x + o.z * d.z),
+            o.x * o.x + o.z * o.z - r2,
+        )
+        .map(|t| {
+            t.filter(|t| {
+                (0.0..=self.height)
+                    .contains(&(o.y + t * d.y))
+            })
+        });
+
+        // hemispheres: the sphere around each end, keeping
+        // only the half that faces away from the other end
+        let sphere = |center_y: f64, outside: f64| {
+            let oc = o - DVec3::new(0., center_y, 0.);
+            solve_quadratic(
+                d.length_squared(),
+                2. * oc.dot(d),
+                oc.length_squared() - r2,
+            )
+            .map(|t| {
+                t.filter(|t| {
+                    (o.y + t * d.y - center_y) * outside
+                        >= 0.
+                })
+            })
+        };
+        let start_cap = sphere(0., -1.);
+        let end_cap = sphere(self.height, 1.);
+
+        let t = side
+            .into_iter()
+            .chain(start_cap)
+            .chain(end_cap)
+            .flatten()
+            .filter(|t| interval.contains(t))
+            .min_by(f64::total_cmp)?;
+
+        let local = o + t * d;
+        // the normal points away from the closest point on
+        // the segment
+        let closest = DVec3::new(
+            0.,
+            local.y.clamp(0., self.height),
+            0.,
+        );
+        let local_normal = (local - closest).normalize();
+
+        let u = angle_around_axis(local);
+        let v = (local.y + self.radius)
+            / (self.height + 2. * self.radius);
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                ray.at(t),
+                self.frame.world_vector(local_normal),
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(
+                self.frame.tangent_around_axis(local),
+            ),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let start = self.frame.origin;
+        let end = start + self.frame.axis * self.height;
+        let extent = DVec3::splat(self.radius);
+        Some(
+            Aabb::new(start - extent, start + extent)
+                .union(&Aabb::new(
+                    end - extent,
+                    end + extent,
+                )),
+        )
+    }
+}
@@ -0,0 +1,164 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::DVec3;
+use std::ops::Range;
+
+use super::{
+    frame::{angle_around_axis, disk_extent, Frame},
+    polynomial::solve_quadratic,
+};
+
+/// A capped cone running from `start` to `end`, with a
+/// different radius at each end. Setting one radius to
+/// zero gives a pointed cone; anything else gives a
+/// frustum.
+///
+/// Like [`Cylinder`](super::cylinder::Cylinder), u goes
+/// around the axis and v from `start` to `end` on the
+/// side, and the caps are mapped onto the unit square.
+pub struct Cone {
+    frame: Frame,
+    height: f64,
+    start_radius: f64,
+    end_radius: f64,
+    material: Material,
+}
+
+impl Cone {
+    pub fn new(
+        start: DVec3,
+        end: DVec3,
+        start_radius: f64,
+        end_radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            frame: Frame::new(start, end - start),
+            height: (end - start).length(),
+            start_radius,
+            end_radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let o = self.frame.local_point(ray.origin);
+        let d = self.frame.local_vector(ray.direction);
+
+        // how fast the radius grows along the axis
+        let k = (self.end_radius - self.start_radius)
+            / self.height;
+
+        // every place the ray crosses the surface, as
+        // (t, outward normal in local space, is a cap)
+        let mut candidates: [Option<(f64, DVec3, bool)>;
+            4] = [None; 4];
+
+        // side: x² + z² = (r0 + k y)²
+        let r_o = self.start_radius + k * o.y;
+        let a = d.x * d.x + d.z * d.z - k * k * d.y * d.y;
+        let b =
+            2. * (o.x * d.x + o.z * d.z - k * d.y * r_o);
+        let c = o.x * o.x + o.z * o.z - r_o * r_o;
+        for (i, t) in
+            solve_quadratic(a, b, c).into_iter().enumerate()
+        {
+            let Some(t) = t else { continue };
+            let p = o + t * d;
+            let radius = self.start_radius + k * p.y;
+            if p.y >= 0.
+                && p.y <= self.height
+                && radius >= 0.
+            {
+                let normal =
+                    DVec3::new(p.x, -k * radius, p.z)
+                        .normalize();
+                candidates[i] = Some((t, normal, false));
+            }
+        }
+
+        // caps at each end with a non-zero radius
+        if d.y.abs() > 1e-12 {
+            for (i, (y, radius, normal)) in [
+                (0., self.start_radius, DVec3::NEG_Y),
+                (self.height, self.end_radius, DVec3::Y),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let t = (y - o.y) / d.y;
+                let p = o + t * d;
+                if p.x * p.x + p.z * p.z < radius * radius {
+                    candidates[2 + i] =
+                        Some((t, normal, true));
+                }
+            }
+        }
+
+        let (t, local_normal, on_cap) = candidates
+            .into_iter()
+            .flatten()
+            .filter(|(t, _, _)| interval.contains(t))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+        let local = o + t * d;
+        let (u, v) = if on_cap {
+            let radius =
+                self.start_radius.max(self.end_radius);
+            (
+                0.5 + 0.5 * local.x / radius,
+                0.5 + 0.5 * local.z / radius,
+            )
+        } else {
+            (
+                angle_around_axis(local),
+                local.y / self.height,
+            )
+        };
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                ray.at(t),
+                self.frame.world_vector(local_normal),
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(if on_cap {
+                self.frame.x
+            } else {
+                self.frame.tangent_around_axis(local)
+            }),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let start = self.frame.origin;
+        let end = start + self.frame.axis * self.height;
+        let start_extent =
+            disk_extent(self.frame.axis, self.start_radius);
+        let end_extent =
+            disk_extent(self.frame.axis, self.end_radius);
+        Some(
+            Aabb::new(
+                start - start_extent,
+                start + start_extent,
+            )
+            .union(&Aabb::new(
+                end - end_extent,
+                end + end_extent,
+            ))
+            .pad(0.0001),
+        )
+    }
+}
@@ -1,19 +1,21 @@
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
-    material::Material,
+    material::{Material, PhaseFunction},
     ray::Ray,
+    sampling::sample_free_flight,
     textures::Texture,
 };
 use glam::DVec3;
-use rand::prelude::*;
-use std::{f64::consts::PI, ops::Range};
+use std::ops::Range;
 
 use super::Shapes;
 
 pub struct ConstantMedium {
     boundary: Box<Shapes>,
-    neg_inv_density: f64,
-    phase_function: Material,
+    mean_free_path: f64,
+    albedo: Texture,
+    phase_function: PhaseFunction,
 }
 
 impl ConstantMedium {
@@ -24,12 +26,20 @@ impl ConstantMedium {
     ) -> Self {
         Self {
             boundary: Box::new(boundary),
-            neg_inv_density: -density.recip(),
-            phase_function: Material::Isotropic {
-                albedo: texture,
-            },
+            mean_free_path: density.recip(),
+            albedo: texture,
+            phase_function: PhaseFunction::Isotropic,
         }
     }
+    /// Scatter light with a different phase function than
+    /// the default isotropic one
+    pub fn with_phase_function(
+        mut self,
+        phase_function: PhaseFunction,
+    ) -> Self {
+        self.phase_function = phase_function;
+        self
+    }
 }
 
 impl Hittable for ConstantMedium {
@@ -38,55 +48,39 @@ impl Hittable for ConstantMedium {
         ray: &Ray,
         interval: Range<f64>,
     ) -> Option<HitRecord> {
-        let mut rng = rand::thread_rng();
-
-        let Some(mut rec1) = self
-            .boundary
-            .hit(ray, f64::NEG_INFINITY..f64::INFINITY)
-        else {
-            return None;
-        };
-        let Some(mut rec2) = self
-            .boundary
-            .hit(ray, (rec1.t + 0.0001)..f64::INFINITY)
-        else {
-            return None;
-        };
-
-        if rec1.t < interval.start {
-            rec1.t = interval.start;
-        }
-        if rec2.t > interval.end {
-            rec2.t = interval.end;
-        }
-
-        if rec1.t >= rec2.t {
-            return None;
-        }
-
-        if rec1.t < 0. {
-            rec1.t = 0.;
-        }
-
         let ray_length = ray.direction.length();
-        let distance_inside_boundary =
-            (rec2.t - rec1.t) * ray_length;
-        let hit_distance =
-            self.neg_inv_density * rng.gen::<f64>().log10();
 
-        if hit_distance > distance_inside_boundary {
-            return None;
+        // The ray can leave and re-enter the boundary any
+        // number of times before it scatters.
+        let mut hit_distance =
+            sample_free_flight(self.mean_free_path);
+        let mut hit_t = None;
+        for Range { start, end } in
+            self.boundary.inside_intervals(ray, interval)
+        {
+            let distance_inside =
+                (end - start) * ray_length;
+            if hit_distance <= distance_inside {
+                hit_t =
+                    Some(start + hit_distance / ray_length);
+                break;
+            }
+            hit_distance -= distance_inside;
         }
-
-        let t = rec1.t + hit_distance / ray_length;
+        let t = hit_t?;
         let point = ray.at(t);
 
         let rec = HitRecord {
             point,
             normal: DVec3::new(1., 0., 0.), // arbitrary
+            tangent: DVec3::new(0., 1., 0.),
+            bitangent: DVec3::new(0., 0., 1.),
             t,
             front_face: true, // also arbitrary
-            material: self.phase_function.clone(),
+            material: Material::Volume {
+                albedo: self.albedo.clone(),
+                phase_function: self.phase_function,
+            },
             // Arbitrary u/v?
             u: 0.,
             v: 0.,
@@ -94,4 +88,120 @@ impl Hittable for ConstantMedium {
 
         Some(rec)
     }
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shapes::{
+        sdf::{Distance, Sdf},
+        sphere::Sphere,
+    };
+
+    /// The fraction of rays that pass through the medium
+    /// without scattering
+    fn measured_transmittance(
+        medium: &ConstantMedium,
+        ray: &Ray,
+    ) -> f64 {
+        let samples = 100_000;
+        let passed = (0..samples)
+            .filter(|_| {
+                medium
+                    .hit(ray, 0.001..f64::INFINITY)
+                    .is_none()
+            })
+            .count();
+        passed as f64 / samples as f64
+    }
+
+    fn sphere(center: DVec3) -> Shapes {
+        Shapes::Sphere(Sphere::new(
+            center,
+            1.,
+            Material::Lambertian {
+                albedo: DVec3::ONE.into(),
+            },
+        ))
+    }
+
+    #[test]
+    fn transmittance_follows_beer_lambert() {
+        let density = 0.5;
+        let medium = ConstantMedium::new(
+            sphere(DVec3::ZERO),
+            density,
+            DVec3::ONE.into(),
+        );
+        let ray = Ray {
+            origin: DVec3::new(0., 0., -5.),
+            direction: DVec3::new(0., 0., 2.),
+            time: 0.,
+        };
+        // the ray crosses the sphere's full diameter
+        let expected = (-density * 2.).exp();
+        let measured =
+            measured_transmittance(&medium, &ray);
+        assert!(
+            (measured - expected).abs() < 0.01,
+            "expected {expected}, measured {measured}"
+        );
+    }
+
+    #[test]
+    fn unbounded_sdf_boundaries_attenuate() {
+        let density = 0.5;
+        let boundary = Shapes::Sdf(Sdf::new(
+            Distance::Sphere { radius: 1. },
+            Material::Lambertian {
+                albedo: DVec3::ONE.into(),
+            },
+        ));
+        let medium = ConstantMedium::new(
+            boundary,
+            density,
+            DVec3::ONE.into(),
+        );
+        let ray = Ray {
+            origin: DVec3::new(0., 0., -5.),
+            direction: DVec3::Z,
+            time: 0.,
+        };
+        let expected = (-density * 2.).exp();
+        let measured =
+            measured_transmittance(&medium, &ray);
+        assert!(
+            (measured - expected).abs() < 0.01,
+            "expected {expected}, measured {measured}"
+        );
+    }
+
+    #[test]
+    fn non_convex_boundaries_count_every_part() {
+        let density = 0.3;
+        let boundary = Shapes::Collection(vec![
+            sphere(DVec3::new(0., 0., -2.)),
+            sphere(DVec3::new(0., 0., 2.)),
+        ]);
+        let medium = ConstantMedium::new(
+            boundary,
+            density,
+            DVec3::ONE.into(),
+        );
+        let ray = Ray {
+            origin: DVec3::new(0., 0., -5.),
+            direction: DVec3::Z,
+            time: 0.,
+        };
+        let expected = (-density * 4.).exp();
+        let measured =
+            measured_transmittance(&medium, &ray);
+        assert!(
+            (measured - expected).abs() < 0.01,
+            "expected {expected}, measured {measured}"
+        );
+    }
 }
@@ -0,0 +1,119 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    ray::Ray,
+};
+use std::ops::Range;
+
+use super::Shapes;
+
+/// How a CSG node combines the volumes of its two shapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Union,
+    Intersection,
+    /// The first shape with the second one cut out of it
+    Difference,
+}
+
+impl Operation {
+    fn contains(
+        self,
+        inside_a: bool,
+        inside_b: bool,
+    ) -> bool {
+        match self {
+            Operation::Union => inside_a || inside_b,
+            Operation::Intersection => inside_a && inside_b,
+            Operation::Difference => inside_a && !inside_b,
+        }
+    }
+}
+
+/// Find where the ray crosses the surface of the combined
+/// shape.
+///
+/// Both shapes have to be closed. We walk along every hit
+/// on either shape, keeping track of whether the ray is
+/// inside each of them, and stop at the first hit that
+/// changes whether it is inside the combination. Hits past
+/// the end of the interval still count towards this, since
+/// a ray can start inside a shape whose surface it only
+/// crosses later on.
+pub(super) fn hit(
+    operation: Operation,
+    a: &Shapes,
+    b: &Shapes,
+    ray: &Ray,
+    interval: Range<f64>,
+) -> Option<HitRecord> {
+    let hits_a =
+        a.all_hits(ray, interval.start..f64::INFINITY);
+    let hits_b =
+        b.all_hits(ray, interval.start..f64::INFINITY);
+
+    // a ray whose first hit leaves a shape starts inside it
+    let mut inside_a =
+        hits_a.first().is_some_and(|hit| !hit.front_face);
+    let mut inside_b =
+        hits_b.first().is_some_and(|hit| !hit.front_face);
+    let inside = operation.contains(inside_a, inside_b);
+
+    let mut hits_a = hits_a.into_iter().peekable();
+    let mut hits_b = hits_b.into_iter().peekable();
+    loop {
+        let from_a = match (hits_a.peek(), hits_b.peek()) {
+            (Some(hit_a), Some(hit_b)) => {
+                hit_a.t <= hit_b.t
+            }
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => return None,
+        };
+        let mut hit_record = if from_a {
+            hits_a.next()?
+        } else {
+            hits_b.next()?
+        };
+        if hit_record.t >= interval.end {
+            return None;
+        }
+        if from_a {
+            inside_a = hit_record.front_face;
+        } else {
+            inside_b = hit_record.front_face;
+        }
+
+        let now_inside =
+            operation.contains(inside_a, inside_b);
+        if now_inside != inside {
+            // The normal already faces the ray. Whether we
+            // are entering depends on the combination, so
+            // the surface of a shape that is cut out faces
+            // the other way.
+            hit_record.front_face = now_inside;
+            return Some(hit_record);
+        }
+    }
+}
+
+pub(super) fn bounding_box(
+    operation: Operation,
+    a: &Shapes,
+    b: &Shapes,
+) -> Option<Aabb> {
+    match operation {
+        Operation::Union => Some(
+            a.bounding_box()?.union(&b.bounding_box()?),
+        ),
+        Operation::Intersection => {
+            match (a.bounding_box(), b.bounding_box()) {
+                (Some(a), Some(b)) => {
+                    Some(a.intersection(&b))
+                }
+                (a, b) => a.or(b),
+            }
+        }
+        Operation::Difference => a.bounding_box(),
+    }
+}
@@ -1,89 +1,170 @@
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     material::Material,
     ray::Ray,
 };
-use glam::{DVec3, Vec3Swizzles};
-use std::ops::Range;
+use glam::DVec3;
+use std::{f64::consts::PI, ops::Range};
 
+use super::frame::disk_extent;
+
+/// A capped cylinder running from `start` to `end`
 pub struct Cylinder {
-    pub start: DVec3,
-    pub end: DVec3,
-    pub radius: f64,
-    pub material: Material,
+    start: DVec3,
+    end: DVec3,
+    radius: f64,
+    material: Material,
+}
+
+impl Cylinder {
+    pub fn new(
+        start: DVec3,
+        end: DVec3,
+        radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            radius,
+            material,
+        }
+    }
+    /// u goes around the axis and v goes from `start` to
+    /// `end` on the side. The caps are mapped onto the
+    /// unit square, with the disk centered in it.
+    fn uv(&self, point: DVec3, on_cap: bool) -> (f64, f64) {
+        let axis = (self.end - self.start).normalize();
+        let (e1, e2) = axis.any_orthonormal_pair();
+        let local = point - self.start;
+        let x = local.dot(e1) / self.radius;
+        let y = local.dot(e2) / self.radius;
+
+        if on_cap {
+            (0.5 + 0.5 * x, 0.5 + 0.5 * y)
+        } else {
+            (
+                (y.atan2(x) + PI) / (2. * PI),
+                local.dot(axis)
+                    / (self.end - self.start).length(),
+            )
+        }
+    }
+    /// The direction u increases in, matching
+    /// [`Cylinder::uv`]
+    fn tangent(&self, point: DVec3, on_cap: bool) -> DVec3 {
+        let axis = (self.end - self.start).normalize();
+        let (e1, e2) = axis.any_orthonormal_pair();
+        if on_cap {
+            e1
+        } else {
+            let local = point - self.start;
+            local.dot(e1) * e2 - local.dot(e2) * e1
+        }
+    }
 }
-// return Some(HitRecord::with_face_normal(
-//     self.material.clone(),
-//     ray.at(tF),
-//     normal,
-//     tF,
-//     ray,
-// ));
+
 impl Hittable for Cylinder {
     fn hit(
         &self,
         ray: &Ray,
         interval: Range<f64>,
     ) -> Option<HitRecord> {
-        let pa = self.start;
-        let pb = self.end;
-        let ca: DVec3 = pb - pa;
-        let oc: DVec3 = ray.origin - pa;
+        let ca: DVec3 = self.end - self.start;
+        let oc: DVec3 = ray.origin - self.start;
 
         let caca: f64 = ca.dot(ca);
         let card: f64 = ca.dot(ray.direction);
         let caoc: f64 = ca.dot(oc);
 
-        let a: f64 = caca - card * card;
+        // every place the ray crosses the surface, as
+        // (t, outward normal, is a cap)
+        let mut candidates: [Option<(f64, DVec3, bool)>;
+            4] = [None; 4];
+
+        // side: the quadratic for the infinite cylinder,
+        // keeping roots between the two caps
+        let a: f64 = caca * ray.direction.length_squared()
+            - card * card;
         let b: f64 =
             caca * oc.dot(ray.direction) - caoc * card;
         let c: f64 = caca * oc.dot(oc)
             - caoc * caoc
             - self.radius * self.radius * caca;
-        let mut h: f64 = b * b - a * c;
-
-        if h < 0. {
-            return None;
+        let h: f64 = b * b - a * c;
+        if a.abs() > 1e-12 && h >= 0. {
+            let h = h.sqrt();
+            for (i, t) in [(-b - h) / a, (-b + h) / a]
+                .into_iter()
+                .enumerate()
+            {
+                let y = caoc + t * card;
+                if y > 0. && y < caca {
+                    let normal = (oc + t * ray.direction
+                        - ca * y / caca)
+                        / self.radius;
+                    candidates[i] =
+                        Some((t, normal, false));
+                }
+            }
         }
 
-        h = h.sqrt();
-        let mut d: f64 = (-b - h) / a;
-
-        let y: f64 = caoc + d * card;
-        if y > 0. && y < caca && interval.contains(&d) {
-            let normal = (oc + d * ray.direction
-                - ca * y / caca)
-                / self.radius;
-            return Some(HitRecord::with_face_normal(
-                self.material.clone(),
-                ray.at(d),
-                // oc + d * ray.direction,
-                normal,
-                d,
-                ray,
-            ));
+        // caps: the two end planes, keeping hits inside
+        // the radius
+        if card.abs() > 1e-12 {
+            for (i, (plane, normal)) in
+                [(0., -ca), (caca, ca)]
+                    .into_iter()
+                    .enumerate()
+            {
+                let t = (plane - caoc) / card;
+                let q = oc + t * ray.direction
+                    - ca * plane / caca;
+                if q.length_squared()
+                    < self.radius * self.radius
+                {
+                    candidates[2 + i] =
+                        Some((t, normal.normalize(), true));
+                }
+            }
         }
 
-        d = ((if y < 0. { 0. } else { caca }) - caoc)
-            / card;
+        let (t, outward_normal, on_cap) = candidates
+            .into_iter()
+            .flatten()
+            .filter(|(t, _, _)| interval.contains(t))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
 
-        if (b + a * d).abs() < h && interval.contains(&d) {
-            let normal =
-                (ca * y.signum() / caca).normalize();
-            return Some(HitRecord::with_face_normal(
+        let point = ray.at(t);
+        let (u, v) = self.uv(point, on_cap);
+        Some(
+            HitRecord::with_face_normal(
                 self.material.clone(),
-                ray.at(d),
-                normal,
-                d,
+                point,
+                outward_normal,
+                t,
                 ray,
-            ));
-        } else {
-            return None;
-        }
+                u,
+                v,
+            )
+            .with_tangent(self.tangent(point, on_cap)),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        // the caps are disks, so each one only reaches out
+        // along an axis as far as the axis direction allows
+        let extent =
+            disk_extent(self.end - self.start, self.radius);
+        Some(
+            Aabb::new(
+                self.start - extent,
+                self.start + extent,
+            )
+            .union(&Aabb::new(
+                self.end - extent,
+                self.end + extent,
+            )),
+        )
     }
 }
-
-// float iCylinder( in vec3 ro, in vec3 rd, in vec2 distBound, inout vec3 normal,
-//     in vec3 pa, in vec3 pb, float ra ) {
-
-// }
@@ -0,0 +1,133 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::DVec3;
+use std::ops::Range;
+
+use super::frame::{angle_around_axis, disk_extent, Frame};
+
+/// A flat, round disk facing along `normal`
+pub struct Disk {
+    annulus: Annulus,
+}
+
+impl Disk {
+    pub fn new(
+        center: DVec3,
+        normal: DVec3,
+        radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            annulus: Annulus::new(
+                center, normal, 0., radius, material,
+            ),
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        self.annulus.hit(ray, interval)
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.annulus.bounding_box()
+    }
+}
+
+/// A flat ring facing along `normal`: a disk with a
+/// round hole in the middle.
+///
+/// u goes around the ring and v goes from the inner edge
+/// to the outer edge.
+pub struct Annulus {
+    frame: Frame,
+    inner_radius: f64,
+    outer_radius: f64,
+    material: Material,
+}
+
+impl Annulus {
+    pub fn new(
+        center: DVec3,
+        normal: DVec3,
+        inner_radius: f64,
+        outer_radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            frame: Frame::new(center, normal),
+            inner_radius,
+            outer_radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Annulus {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let denom = self.frame.axis.dot(ray.direction);
+
+        // No hit if the ray is parallel to the plane.
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+        let t = self
+            .frame
+            .axis
+            .dot(self.frame.origin - ray.origin)
+            / denom;
+        if !interval.contains(&t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let local = self.frame.local_point(point);
+        let distance = local.length();
+        if distance < self.inner_radius
+            || distance > self.outer_radius
+        {
+            return None;
+        }
+
+        let u = angle_around_axis(local);
+        let v = (distance - self.inner_radius)
+            / (self.outer_radius - self.inner_radius);
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                point,
+                self.frame.axis,
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(
+                self.frame.tangent_around_axis(local),
+            ),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent =
+            disk_extent(self.frame.axis, self.outer_radius);
+        Some(
+            Aabb::new(
+                self.frame.origin - extent,
+                self.frame.origin + extent,
+            )
+            .pad(0.0001),
+        )
+    }
+}
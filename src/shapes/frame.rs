@@ -0,0 +1,65 @@
+use glam::DVec3;
+use std::f64::consts::PI;
+
+/// An orthonormal frame used by the shapes that are
+/// defined around an axis. In local coordinates the axis
+/// is +y and the origin sits at `origin`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Frame {
+    pub origin: DVec3,
+    pub x: DVec3,
+    pub axis: DVec3,
+    pub z: DVec3,
+}
+
+impl Frame {
+    pub fn new(origin: DVec3, axis: DVec3) -> Self {
+        let axis = axis.normalize();
+        let (x, z) = axis.any_orthonormal_pair();
+        Self { origin, x, axis, z }
+    }
+    pub fn local_point(&self, point: DVec3) -> DVec3 {
+        self.local_vector(point - self.origin)
+    }
+    pub fn local_vector(&self, vector: DVec3) -> DVec3 {
+        DVec3::new(
+            vector.dot(self.x),
+            vector.dot(self.axis),
+            vector.dot(self.z),
+        )
+    }
+    pub fn world_vector(&self, vector: DVec3) -> DVec3 {
+        vector.x * self.x
+            + vector.y * self.axis
+            + vector.z * self.z
+    }
+    /// The world direction a local point moves in as its
+    /// [`angle_around_axis`] increases
+    pub fn tangent_around_axis(
+        &self,
+        local: DVec3,
+    ) -> DVec3 {
+        self.world_vector(DVec3::new(-local.z, 0., local.x))
+    }
+}
+
+/// The fraction of the way around the local y axis that
+/// a local point sits, in [0,1].
+pub(crate) fn angle_around_axis(local: DVec3) -> f64 {
+    (local.z.atan2(local.x) + PI) / (2. * PI)
+}
+
+/// How far a disk of `radius` perpendicular to `axis`
+/// reaches along each world axis from its center.
+pub(crate) fn disk_extent(
+    axis: DVec3,
+    radius: f64,
+) -> DVec3 {
+    let axis = axis.normalize();
+    radius
+        * DVec3::new(
+            (1. - axis.x * axis.x).max(0.).sqrt(),
+            (1. - axis.y * axis.y).max(0.).sqrt(),
+            (1. - axis.z * axis.z).max(0.).sqrt(),
+        )
+}
@@ -0,0 +1,411 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::{DVec2, DVec3};
+use noise::NoiseFn;
+use std::{io, ops::Range, path::Path};
+
+/// A terrain surface built from a grid of height samples.
+///
+/// The grid is stretched over the x and z extents of its
+/// bounds, with a height of 0 at the bottom of the bounds
+/// and 1 at the top. Each grid cell is split into two
+/// triangles, and normals are blended across them so the
+/// surface looks smooth.
+///
+/// Rays walk the cells they pass over with a 2D DDA and
+/// skip any cell they pass above or below.
+pub struct Heightfield {
+    heights: Vec<f64>,
+    /// The lowest and highest height in each cell
+    cell_ranges: Vec<(f64, f64)>,
+    columns: usize,
+    rows: usize,
+    bounds: Aabb,
+    material: Material,
+}
+
+impl Heightfield {
+    /// A heightfield from `columns` by `rows` samples, with
+    /// the samples of each row next to each other. Rows run
+    /// along z and columns along x.
+    pub fn new(
+        heights: Vec<f64>,
+        columns: usize,
+        rows: usize,
+        bounds: Aabb,
+        material: Material,
+    ) -> Self {
+        assert!(
+            columns >= 2 && rows >= 2,
+            "a heightfield needs at least 2x2 samples"
+        );
+        assert_eq!(
+            heights.len(),
+            columns * rows,
+            "heights should have columns * rows samples"
+        );
+
+        let cell_ranges = (0..rows - 1)
+            .flat_map(|j| {
+                (0..columns - 1).map(move |i| (i, j))
+            })
+            .map(|(i, j)| {
+                [
+                    (i, j),
+                    (i + 1, j),
+                    (i, j + 1),
+                    (i + 1, j + 1),
+                ]
+                .into_iter()
+                .map(|(i, j)| heights[j * columns + i])
+                .fold(
+                    (f64::INFINITY, f64::NEG_INFINITY),
+                    |(min, max), h| {
+                        (min.min(h), max.max(h))
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            heights,
+            cell_ranges,
+            columns,
+            rows,
+            bounds,
+            material,
+        }
+    }
+    /// Sample `height` on a grid of `columns` by `rows`
+    /// points. It is given x and z coordinates between 0
+    /// and 1 and should return heights between 0 and 1.
+    pub fn from_fn(
+        columns: usize,
+        rows: usize,
+        bounds: Aabb,
+        material: Material,
+        height: impl Fn(f64, f64) -> f64,
+    ) -> Self {
+        let heights = (0..rows)
+            .flat_map(|j| (0..columns).map(move |i| (i, j)))
+            .map(|(i, j)| {
+                height(
+                    i as f64 / (columns - 1) as f64,
+                    j as f64 / (rows - 1) as f64,
+                )
+            })
+            .collect();
+        Self::new(heights, columns, rows, bounds, material)
+    }
+    /// Sample a noise function such as
+    /// [`Perlin`](noise::Perlin) across the grid, with
+    /// `frequency` features along each side. The noise is
+    /// mapped from [-1,1] onto the full height of the
+    /// bounds.
+    pub fn from_noise(
+        noise: &impl NoiseFn<f64, 2>,
+        frequency: f64,
+        columns: usize,
+        rows: usize,
+        bounds: Aabb,
+        material: Material,
+    ) -> Self {
+        Self::from_fn(
+            columns,
+            rows,
+            bounds,
+            material,
+            |x, z| {
+                let value = noise
+                    .get([x * frequency, z * frequency]);
+                (0.5 + 0.5 * value).clamp(0., 1.)
+            },
+        )
+    }
+    /// Use the brightness of a grayscale image as the
+    /// heights, one sample per pixel. The top of the image
+    /// is at the far (-z) end of the bounds, so that the
+    /// image lines up with [`Texture::Image`] lookups of
+    /// the heightfield's UVs.
+    ///
+    /// [`Texture::Image`]: crate::textures::Texture::Image
+    pub fn load_image<P>(
+        path: P,
+        bounds: Aabb,
+        material: Material,
+    ) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        use image::io::Reader as ImageReader;
+
+        let img = ImageReader::open(path)?
+            .decode()
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    error,
+                )
+            })?
+            .into_luma16();
+
+        let (columns, rows) = img.dimensions();
+        if columns < 2 || rows < 2 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("a heightfield needs at least 2x2 pixels, but the image is {columns}x{rows}"),
+            ));
+        }
+        let heights = img
+            .pixels()
+            .map(|pixel| pixel[0] as f64 / u16::MAX as f64)
+            .collect();
+        Ok(Self::new(
+            heights,
+            columns as usize,
+            rows as usize,
+            bounds,
+            material,
+        ))
+    }
+
+    fn size(&self) -> DVec3 {
+        self.bounds.max - self.bounds.min
+    }
+    /// The world-space distance between grid points
+    fn spacing(&self) -> DVec3 {
+        let size = self.size();
+        DVec3::new(
+            size.x / (self.columns - 1) as f64,
+            size.y,
+            size.z / (self.rows - 1) as f64,
+        )
+    }
+    fn height(&self, i: usize, j: usize) -> f64 {
+        self.heights[j * self.columns + i]
+    }
+    fn vertex(&self, i: usize, j: usize) -> DVec3 {
+        self.bounds.min
+            + DVec3::new(
+                i as f64,
+                self.height(i, j),
+                j as f64,
+            ) * self.spacing()
+    }
+    /// A normal at a grid point from the slope to its
+    /// neighbors
+    fn vertex_normal(&self, i: usize, j: usize) -> DVec3 {
+        let spacing = self.spacing();
+        let (left, right) = (
+            i.saturating_sub(1),
+            (i + 1).min(self.columns - 1),
+        );
+        let (back, front) = (
+            j.saturating_sub(1),
+            (j + 1).min(self.rows - 1),
+        );
+        let slope_x = (self.height(right, j)
+            - self.height(left, j))
+            * spacing.y
+            / ((right - left) as f64 * spacing.x);
+        let slope_z = (self.height(i, front)
+            - self.height(i, back))
+            * spacing.y
+            / ((front - back) as f64 * spacing.z);
+        DVec3::new(-slope_x, 1., -slope_z).normalize()
+    }
+    /// Intersect the two triangles of the cell with its
+    /// lowest corner at grid point `i`,`j`
+    fn hit_cell(
+        &self,
+        i: usize,
+        j: usize,
+        ray: &Ray,
+        interval: &Range<f64>,
+    ) -> Option<HitRecord> {
+        let triangles = [
+            [(i, j), (i + 1, j), (i + 1, j + 1)],
+            [(i, j), (i + 1, j + 1), (i, j + 1)],
+        ];
+        let (t, corners, u, v) = triangles
+            .into_iter()
+            .filter_map(|corners| {
+                let [a, b, c] =
+                    corners.map(|(i, j)| self.vertex(i, j));
+                let (t, u, v) =
+                    intersect_triangle(ray, a, b, c)?;
+                interval
+                    .contains(&t)
+                    .then_some((t, corners, u, v))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+        let [a, b, c] =
+            corners.map(|(i, j)| self.vertex_normal(i, j));
+        let outward_normal =
+            ((1. - u - v) * a + u * b + v * c).normalize();
+
+        let point = ray.at(t);
+        let grid =
+            (point - self.bounds.min) / self.spacing();
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                point,
+                outward_normal,
+                t,
+                ray,
+                grid.x / (self.columns - 1) as f64,
+                1. - grid.z / (self.rows - 1) as f64,
+            )
+            .with_tangent(uv_tangent(
+                corners.map(|(i, j)| self.vertex(i, j)),
+                corners.map(|(i, j)| self.vertex_uv(i, j)),
+            )),
+        )
+    }
+    /// The UV coordinates of grid point `i`,`j`
+    fn vertex_uv(&self, i: usize, j: usize) -> DVec2 {
+        DVec2::new(
+            i as f64 / (self.columns - 1) as f64,
+            1. - j as f64 / (self.rows - 1) as f64,
+        )
+    }
+}
+
+impl Hittable for Heightfield {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let Range { start, end } =
+            self.bounds.hit(ray, interval.clone())?;
+
+        // work in grid coordinates, where cells are 1 wide
+        let spacing = self.spacing();
+        let origin =
+            (ray.origin - self.bounds.min) / spacing;
+        let direction = ray.direction / spacing;
+        let entry = origin + start * direction;
+
+        let last_column = (self.columns - 2) as i64;
+        let last_row = (self.rows - 2) as i64;
+        let mut i =
+            (entry.x.floor() as i64).clamp(0, last_column);
+        let mut j =
+            (entry.z.floor() as i64).clamp(0, last_row);
+
+        // how far along the ray each step to the next
+        // column or row is, and where the next one is
+        let step_i = direction.x.signum() as i64;
+        let step_j = direction.z.signum() as i64;
+        let delta_i = direction.x.abs().recip();
+        let delta_j = direction.z.abs().recip();
+        let mut next_i = if direction.x > 0. {
+            ((i + 1) as f64 - origin.x) / direction.x
+        } else {
+            (i as f64 - origin.x) / direction.x
+        };
+        let mut next_j = if direction.z > 0. {
+            ((j + 1) as f64 - origin.z) / direction.z
+        } else {
+            (j as f64 - origin.z) / direction.z
+        };
+        // rays straight up or down never leave their cell
+        if direction.x == 0. {
+            next_i = f64::INFINITY;
+        }
+        if direction.z == 0. {
+            next_j = f64::INFINITY;
+        }
+
+        let mut cell_start = start;
+        while (0..=last_column).contains(&i)
+            && (0..=last_row).contains(&j)
+            && cell_start <= end
+        {
+            let cell_end = next_i.min(next_j).min(end);
+            let (low, high) = self.cell_ranges[j as usize
+                * (self.columns - 1)
+                + i as usize];
+            let y_start =
+                origin.y + cell_start * direction.y;
+            let y_end = origin.y + cell_end * direction.y;
+            if y_start.min(y_end) <= high
+                && y_start.max(y_end) >= low
+            {
+                if let Some(hit_record) = self.hit_cell(
+                    i as usize, j as usize, ray, &interval,
+                ) {
+                    return Some(hit_record);
+                }
+            }
+
+            if next_i < next_j {
+                cell_start = next_i;
+                next_i += delta_i;
+                i += step_i;
+            } else {
+                cell_start = next_j;
+                next_j += delta_j;
+                j += step_j;
+            }
+        }
+        None
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(self.bounds.pad(0.0001))
+    }
+}
+
+/// Möller–Trumbore ray/triangle intersection, returning
+/// `t` and the barycentric coordinates of `b` and `c`
+fn intersect_triangle(
+    ray: &Ray,
+    a: DVec3,
+    b: DVec3,
+    c: DVec3,
+) -> Option<(f64, f64, f64)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = det.recip();
+    let s = ray.origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inv_det;
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+    Some((edge2.dot(q) * inv_det, u, v))
+}
+
+/// The direction u increases in across a triangle, from
+/// how its positions change with its UV coordinates
+fn uv_tangent(
+    positions: [DVec3; 3],
+    uvs: [DVec2; 3],
+) -> DVec3 {
+    let (e1, e2) = (
+        positions[1] - positions[0],
+        positions[2] - positions[0],
+    );
+    let (d1, d2) = (uvs[1] - uvs[0], uvs[2] - uvs[0]);
+    let determinant = d1.x * d2.y - d2.x * d1.y;
+    if determinant.abs() < 1e-12 {
+        return DVec3::ZERO;
+    }
+    (e1 * d2.y - e2 * d1.y) / determinant
+}
@@ -0,0 +1,240 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::{Material, PhaseFunction},
+    ray::Ray,
+    textures::Texture,
+};
+use glam::DVec3;
+use rand::prelude::*;
+use std::{ops::Range, sync::Arc};
+
+use super::Shapes;
+
+/// Where a [`HeterogeneousMedium`] is dense, as a fraction
+/// of its maximum density. Values are clamped to [0,1].
+#[derive(Clone)]
+pub enum Density {
+    /// The average of a texture's color channels, such as
+    /// Perlin noise or turbulence
+    Texture(Texture),
+    Grid(VoxelGrid),
+    Function(Arc<dyn Fn(DVec3) -> f64 + Send + Sync>),
+}
+
+impl Density {
+    pub fn function(
+        f: impl Fn(DVec3) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Density::Function(Arc::new(f))
+    }
+    pub fn value(&self, point: DVec3) -> f64 {
+        let value = match self {
+            Density::Texture(texture) => {
+                let color = texture.color(0., 0., point);
+                (color.x + color.y + color.z) / 3.
+            }
+            Density::Grid(grid) => grid.value(point),
+            Density::Function(f) => f(point),
+        };
+        value.clamp(0., 1.)
+    }
+}
+
+/// Values on a regular 3D grid of points spread across
+/// `bounds`, blended trilinearly between the points and 0
+/// outside of the bounds.
+#[derive(Clone, Debug)]
+pub struct VoxelGrid {
+    values: Vec<f64>,
+    resolution: [usize; 3],
+    bounds: Aabb,
+}
+
+impl VoxelGrid {
+    /// A grid with `resolution` points along x, y and z.
+    /// The values are ordered by x first, then y, then z.
+    pub fn new(
+        values: Vec<f64>,
+        resolution: [usize; 3],
+        bounds: Aabb,
+    ) -> Self {
+        assert!(
+            resolution.iter().all(|n| *n >= 2),
+            "a voxel grid needs at least 2 points per axis"
+        );
+        assert_eq!(
+            values.len(),
+            resolution.iter().product::<usize>(),
+            "values should have one entry per grid point"
+        );
+        Self {
+            values,
+            resolution,
+            bounds,
+        }
+    }
+    /// Fill a grid by calling `f` at every grid point
+    pub fn from_fn(
+        resolution: [usize; 3],
+        bounds: Aabb,
+        f: impl Fn(DVec3) -> f64,
+    ) -> Self {
+        let [nx, ny, nz] = resolution;
+        let size = bounds.max - bounds.min;
+        let values = (0..nz)
+            .flat_map(|k| {
+                (0..ny).flat_map(move |j| {
+                    (0..nx).map(move |i| (i, j, k))
+                })
+            })
+            .map(|(i, j, k)| {
+                f(bounds.min
+                    + size
+                        * DVec3::new(
+                            i as f64 / (nx - 1) as f64,
+                            j as f64 / (ny - 1) as f64,
+                            k as f64 / (nz - 1) as f64,
+                        ))
+            })
+            .collect();
+        Self::new(values, resolution, bounds)
+    }
+    pub fn value(&self, point: DVec3) -> f64 {
+        let [nx, ny, nz] = self.resolution;
+        let last =
+            DVec3::new(nx as f64, ny as f64, nz as f64)
+                - 1.;
+        let grid = (point - self.bounds.min)
+            / (self.bounds.max - self.bounds.min)
+            * last;
+        if grid.cmplt(DVec3::ZERO).any()
+            || grid.cmpgt(last).any()
+        {
+            return 0.;
+        }
+
+        // the lower corner of the cell, kept inside the
+        // grid so points on the far faces still have a
+        // cell to blend
+        let cell = grid.floor().min(last - 1.);
+        let f = grid - cell;
+        let (i, j, k) = (
+            cell.x as usize,
+            cell.y as usize,
+            cell.z as usize,
+        );
+        let at = |di: usize, dj: usize, dk: usize| {
+            self.values
+                [((k + dk) * ny + j + dj) * nx + i + di]
+        };
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let x00 = lerp(at(0, 0, 0), at(1, 0, 0), f.x);
+        let x10 = lerp(at(0, 1, 0), at(1, 1, 0), f.x);
+        let x01 = lerp(at(0, 0, 1), at(1, 0, 1), f.x);
+        let x11 = lerp(at(0, 1, 1), at(1, 1, 1), f.x);
+        lerp(lerp(x00, x10, f.y), lerp(x01, x11, f.y), f.z)
+    }
+}
+
+/// A participating medium, like smoke or cloud, whose
+/// density changes from place to place.
+///
+/// Free-flight distances are sampled with delta tracking:
+/// we take exponential steps as if the whole medium were
+/// as dense as `max_density` and accept each step as a
+/// real collision with probability `density /
+/// max_density`.
+pub struct HeterogeneousMedium {
+    boundary: Box<Shapes>,
+    density: Density,
+    max_density: f64,
+    albedo: Texture,
+    phase_function: PhaseFunction,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(
+        boundary: Shapes,
+        density: Density,
+        max_density: f64,
+        texture: Texture,
+    ) -> Self {
+        Self {
+            boundary: Box::new(boundary),
+            density,
+            max_density,
+            albedo: texture,
+            phase_function: PhaseFunction::Isotropic,
+        }
+    }
+    /// Scatter light with a different phase function than
+    /// the default isotropic one
+    pub fn with_phase_function(
+        mut self,
+        phase_function: PhaseFunction,
+    ) -> Self {
+        self.phase_function = phase_function;
+        self
+    }
+    pub fn density(&self, point: DVec3) -> f64 {
+        self.max_density * self.density.value(point)
+    }
+    /// A distance to the next collision, real or null,
+    /// with the majorant density
+    fn free_flight(&self, rng: &mut impl Rng) -> f64 {
+        -(1. - rng.gen::<f64>()).ln() / self.max_density
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let mut rng = rand::thread_rng();
+        let ray_length = ray.direction.length();
+
+        for Range { start, end } in
+            self.boundary.inside_intervals(
+                ray,
+                interval.start.max(0.)..interval.end,
+            )
+        {
+            let mut t = start;
+            loop {
+                t +=
+                    self.free_flight(&mut rng) / ray_length;
+                if t >= end {
+                    break;
+                }
+                let point = ray.at(t);
+                if rng.gen::<f64>() * self.max_density
+                    < self.density(point)
+                {
+                    return Some(HitRecord {
+                        point,
+                        normal: DVec3::new(1., 0., 0.), // arbitrary
+                        tangent: DVec3::new(0., 1., 0.),
+                        bitangent: DVec3::new(0., 0., 1.),
+                        t,
+                        front_face: true, // also arbitrary
+                        material: Material::Volume {
+                            albedo: self.albedo.clone(),
+                            phase_function: self
+                                .phase_function,
+                        },
+                        u: 0.,
+                        v: 0.,
+                    });
+                }
+            }
+        }
+        None
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.boundary.bounding_box()
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::DVec3;
+use std::ops::Range;
+
+use super::{
+    frame::{angle_around_axis, disk_extent, Frame},
+    polynomial::solve_quadratic,
+};
+
+/// A bowl-shaped paraboloid with its tip at `vertex`,
+/// opening along `axis`. It reaches `radius` at `height`
+/// along the axis, where it is closed off by a flat cap.
+///
+/// u goes around the axis and v goes from the tip to the
+/// rim on the curved surface. The cap is mapped onto the
+/// unit square.
+pub struct Paraboloid {
+    frame: Frame,
+    height: f64,
+    radius: f64,
+    material: Material,
+}
+
+impl Paraboloid {
+    pub fn new(
+        vertex: DVec3,
+        axis: DVec3,
+        height: f64,
+        radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            frame: Frame::new(vertex, axis),
+            height,
+            radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Paraboloid {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let o = self.frame.local_point(ray.origin);
+        let d = self.frame.local_vector(ray.direction);
+
+        // x² + z² = k y, with k picked so the surface
+        // reaches the radius at the height
+        let k = self.radius * self.radius / self.height;
+
+        // every place the ray crosses the surface, as
+        // (t, outward normal in local space, is the cap)
+        let mut candidates: [Option<(f64, DVec3, bool)>;
+            3] = [None; 3];
+
+        let roots = solve_quadratic(
+            d.x * d.x + d.z * d.z,
+            2. * (o.x * d.x + o.z * d.z) - k * d.y,
+            o.x * o.x + o.z * o.z - k * o.y,
+        );
+        for (i, t) in roots.into_iter().enumerate() {
+            let Some(t) = t else { continue };
+            let p = o + t * d;
+            if p.y <= self.height {
+                let normal =
+                    DVec3::new(2. * p.x, -k, 2. * p.z)
+                        .normalize();
+                candidates[i] = Some((t, normal, false));
+            }
+        }
+
+        if d.y.abs() > 1e-12 {
+            let t = (self.height - o.y) / d.y;
+            let p = o + t * d;
+            if p.x * p.x + p.z * p.z
+                < self.radius * self.radius
+            {
+                candidates[2] = Some((t, DVec3::Y, true));
+            }
+        }
+
+        let (t, local_normal, on_cap) = candidates
+            .into_iter()
+            .flatten()
+            .filter(|(t, _, _)| interval.contains(t))
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+
+        let local = o + t * d;
+        let (u, v) = if on_cap {
+            (
+                0.5 + 0.5 * local.x / self.radius,
+                0.5 + 0.5 * local.z / self.radius,
+            )
+        } else {
+            (
+                angle_around_axis(local),
+                local.y / self.height,
+            )
+        };
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                ray.at(t),
+                self.frame.world_vector(local_normal),
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(if on_cap {
+                self.frame.x
+            } else {
+                self.frame.tangent_around_axis(local)
+            }),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let vertex = self.frame.origin;
+        let rim = vertex + self.frame.axis * self.height;
+        let extent =
+            disk_extent(self.frame.axis, self.radius);
+        Some(
+            Aabb::new(vertex - extent, vertex + extent)
+                .union(&Aabb::new(
+                    rim - extent,
+                    rim + extent,
+                )),
+        )
+    }
+}
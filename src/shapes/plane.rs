@@ -0,0 +1,106 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::DVec3;
+use std::ops::Range;
+
+use super::frame::Frame;
+
+/// An infinite plane through `point`, facing along
+/// `normal`.
+///
+/// UVs are a planar projection of world space onto the
+/// plane, so procedural textures line up with the rest of
+/// the scene. Without tiling they are the distances along
+/// the plane's two in-plane axes; with tiling they wrap
+/// into [0,1] every `tile_size` units, which repeats an
+/// image texture across the plane.
+pub struct Plane {
+    frame: Frame,
+    tile_size: Option<f64>,
+    material: Material,
+}
+
+impl Plane {
+    pub fn new(
+        point: DVec3,
+        normal: DVec3,
+        material: Material,
+    ) -> Self {
+        let frame = Frame::new(point, normal);
+        Self {
+            // project from the world origin so UVs don't
+            // depend on which point was passed in
+            frame: Frame {
+                origin: frame.axis * frame.axis.dot(point),
+                ..frame
+            },
+            tile_size: None,
+            material,
+        }
+    }
+    /// A horizontal ground plane at `height`, facing up
+    pub fn ground(height: f64, material: Material) -> Self {
+        Self::new(
+            DVec3::new(0., height, 0.),
+            DVec3::Y,
+            material,
+        )
+    }
+    /// Repeat UVs every `tile_size` units along the plane
+    pub fn with_tiling(mut self, tile_size: f64) -> Self {
+        self.tile_size = Some(tile_size);
+        self
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let denom = self.frame.axis.dot(ray.direction);
+
+        // No hit if the ray is parallel to the plane.
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+        let t = self
+            .frame
+            .axis
+            .dot(self.frame.origin - ray.origin)
+            / denom;
+        if !interval.contains(&t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let local = self.frame.local_point(point);
+        let (u, v) = match self.tile_size {
+            Some(size) => (
+                (local.x / size).rem_euclid(1.),
+                (local.z / size).rem_euclid(1.),
+            ),
+            None => (local.x, local.z),
+        };
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                point,
+                self.frame.axis,
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(self.frame.x),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        None
+    }
+}
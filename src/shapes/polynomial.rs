@@ -0,0 +1,175 @@
+use std::f64::consts::PI;
+
+/// Real roots of `a t² + b t + c`, falling back to the
+/// linear solution when `a` is 0.
+pub(crate) fn solve_quadratic(
+    a: f64,
+    b: f64,
+    c: f64,
+) -> [Option<f64>; 2] {
+    if a == 0. {
+        if b == 0. {
+            return [None, None];
+        }
+        return [Some(-c / b), None];
+    }
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return [None, None];
+    }
+    // adding terms of the same sign never cancels, so both
+    // roots keep their precision at any scale, and the
+    // root near -c/b stays accurate when `a` is tiny next
+    // to `b` and `c` while the other one runs off to
+    // infinity
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0. {
+        return [Some(0.), Some(0.)];
+    }
+    let (t0, t1) = (q / a, c / q);
+    [Some(t0.min(t1)), Some(t0.max(t1))]
+}
+
+/// Real roots of `x⁴ + a x³ + b x² + c x + d`, found with
+/// Ferrari's method and then polished with Newton's
+/// method to win back the precision the closed form loses.
+pub(crate) fn solve_quartic(
+    [a, b, c, d]: [f64; 4],
+) -> [Option<f64>; 4] {
+    // substitute x = y - a/4 to remove the cubic term:
+    // y⁴ + p y² + q y + r = 0
+    let a2 = a * a;
+    let p = -3. / 8. * a2 + b;
+    let q = a2 * a / 8. - a * b / 2. + c;
+    let r = -3. / 256. * a2 * a2 + a2 * b / 16.
+        - a * c / 4.
+        + d;
+
+    let mut roots = [None; 4];
+    if r.abs() < 1e-12 {
+        // no constant term: y (y³ + p y + q) = 0
+        roots[0] = Some(0.);
+        let cubic = solve_cubic([0., p, q]);
+        roots[1..].copy_from_slice(&cubic);
+    } else {
+        // split into two quadratics using one root of the
+        // resolvent cubic
+        let z = solve_cubic([
+            -p / 2.,
+            -r,
+            r * p / 2. - q * q / 8.,
+        ])[0]
+            .expect("a cubic always has a real root");
+
+        let u = z * z - r;
+        let v = 2. * z - p;
+        let u = if u.abs() < 1e-12 {
+            0.
+        } else if u > 0. {
+            u.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if v.abs() < 1e-12 {
+            0.
+        } else if v > 0. {
+            v.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if q < 0. { -v } else { v };
+
+        let first = solve_monic_quadratic(v, z - u);
+        let second = solve_monic_quadratic(-v, z + u);
+        roots[..2].copy_from_slice(&first);
+        roots[2..].copy_from_slice(&second);
+    }
+
+    roots.map(|root| {
+        let mut x = root? - a / 4.;
+        for _ in 0..2 {
+            let f = (((x + a) * x + b) * x + c) * x + d;
+            let df =
+                ((4. * x + 3. * a) * x + 2. * b) * x + c;
+            if df.abs() > 1e-12 {
+                x -= f / df;
+            }
+        }
+        Some(x)
+    })
+}
+
+/// Real roots of `x³ + a x² + b x + c`. The first root is
+/// always present.
+fn solve_cubic([a, b, c]: [f64; 3]) -> [Option<f64>; 3] {
+    // substitute x = y - a/3: y³ + 3p y + 2q = 0
+    let a2 = a * a;
+    let p = (-a2 / 3. + b) / 3.;
+    let q = (2. / 27. * a * a2 - a * b / 3. + c) / 2.;
+    let p3 = p * p * p;
+    let discriminant = q * q + p3;
+    let offset = a / 3.;
+
+    if discriminant.abs() < 1e-14 {
+        if q.abs() < 1e-14 {
+            // one triple root
+            [Some(-offset), None, None]
+        } else {
+            // one single and one double root
+            let u = (-q).cbrt();
+            [Some(2. * u - offset), Some(-u - offset), None]
+        }
+    } else if discriminant < 0. {
+        // three real roots
+        let phi =
+            (-q / (-p3).sqrt()).clamp(-1., 1.).acos() / 3.;
+        let t = 2. * (-p).sqrt();
+        [
+            Some(t * phi.cos() - offset),
+            Some(-t * (phi + PI / 3.).cos() - offset),
+            Some(-t * (phi - PI / 3.).cos() - offset),
+        ]
+    } else {
+        // one real root
+        let sqrt_d = discriminant.sqrt();
+        let u = (sqrt_d - q).cbrt();
+        let v = -(sqrt_d + q).cbrt();
+        [Some(u + v - offset), None, None]
+    }
+}
+
+/// Real roots of `x² + b x + c`
+fn solve_monic_quadratic(
+    b: f64,
+    c: f64,
+) -> [Option<f64>; 2] {
+    let discriminant = b * b / 4. - c;
+    if discriminant < 0. {
+        return [None, None];
+    }
+    let sqrt_d = discriminant.sqrt();
+    [Some(-b / 2. - sqrt_d), Some(-b / 2. + sqrt_d)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadratic_roots_hold_at_any_scale() {
+        // the quadratic of a ray crossing a unit circle,
+        // with its direction scaled by `s`
+        for s in [1e-8, 1e-3, 1., 1e3, 1e8] {
+            let [t0, t1] =
+                solve_quadratic(s * s, -10. * s, 24.);
+            let (t0, t1) = (t0.unwrap(), t1.unwrap());
+            for (t, expected) in [(t0, 4.), (t1, 6.)] {
+                assert!(
+                    (t * s - expected).abs() < 1e-9,
+                    "scale {s}: expected {expected}, found {}",
+                    t * s
+                );
+            }
+        }
+    }
+}
@@ -1,14 +1,133 @@
 use std::{f64::consts::PI, ops::Range};
 
-use glam::DVec3;
+use glam::{DVec2, DVec3};
 
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     material::Material,
     ray::Ray,
     textures::Texture,
 };
 
+/// The outline of a planar shape, given in the plane
+/// coordinates of a [`Quad`]: `a` runs along `u` and `b`
+/// along `v`, starting from `Q`.
+#[derive(Clone, Debug, Default)]
+pub enum QuadShape {
+    /// The parallelogram with corners `Q`, `Q + u`,
+    /// `Q + v` and `Q + u + v`
+    #[default]
+    Parallelogram,
+    /// The triangle with corners `Q`, `Q + u` and `Q + v`
+    Triangle,
+    /// The ellipse centered on `Q` with `u` and `v` as its
+    /// two semi-axes. Perpendicular `u` and `v` of the same
+    /// length make a disk.
+    Ellipse,
+    /// A polygon with any number of holes. Points inside
+    /// an odd number of the rings are inside the shape.
+    Polygon {
+        outline: Vec<DVec2>,
+        holes: Vec<Vec<DVec2>>,
+    },
+}
+
+impl QuadShape {
+    /// Given the hit point in plane coordinates, return
+    /// `None` if it is outside the shape, otherwise return
+    /// the hit record's UV coordinates.
+    fn interior(
+        &self,
+        a: f64,
+        b: f64,
+    ) -> Option<(f64, f64)> {
+        match self {
+            QuadShape::Parallelogram => {
+                if (a < 0.)
+                    || (1. < a)
+                    || (b < 0.)
+                    || (1. < b)
+                {
+                    return None;
+                }
+                // a,b == u,v
+                Some((a, b))
+            }
+            QuadShape::Triangle => {
+                if a < 0. || b < 0. || a + b > 1. {
+                    return None;
+                }
+                Some((a, b))
+            }
+            QuadShape::Ellipse => {
+                if a * a + b * b > 1. {
+                    return None;
+                }
+                Some((0.5 + 0.5 * a, 0.5 + 0.5 * b))
+            }
+            QuadShape::Polygon { outline, holes } => {
+                let point = DVec2::new(a, b);
+                let crossings = std::iter::once(outline)
+                    .chain(holes)
+                    .filter(|ring| {
+                        ring_contains(ring, point)
+                    })
+                    .count();
+                if crossings % 2 == 0 {
+                    return None;
+                }
+                Some((a, b))
+            }
+        }
+    }
+    /// The smallest and largest plane coordinates the
+    /// shape covers
+    fn bounds(&self) -> (DVec2, DVec2) {
+        match self {
+            QuadShape::Parallelogram
+            | QuadShape::Triangle => {
+                (DVec2::ZERO, DVec2::ONE)
+            }
+            QuadShape::Ellipse => (-DVec2::ONE, DVec2::ONE),
+            QuadShape::Polygon { outline, .. } => {
+                outline.iter().fold(
+                    (
+                        DVec2::splat(f64::INFINITY),
+                        DVec2::splat(f64::NEG_INFINITY),
+                    ),
+                    |(min, max), point| {
+                        (min.min(*point), max.max(*point))
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// Even-odd test for whether a closed ring of points
+/// surrounds `point`
+fn ring_contains(ring: &[DVec2], point: DVec2) -> bool {
+    let mut inside = false;
+    let mut previous = match ring.last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    for &current in ring {
+        if (current.y > point.y) != (previous.y > point.y) {
+            let x = current.x
+                + (point.y - current.y)
+                    * (previous.x - current.x)
+                    / (previous.y - current.y);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
 pub struct Quad {
     Q: DVec3,
     u: DVec3,
@@ -17,6 +136,7 @@ pub struct Quad {
     normal: DVec3,
     D: f64,
     w: DVec3,
+    shape: QuadShape,
 }
 
 impl Quad {
@@ -38,18 +158,34 @@ impl Quad {
             normal,
             D,
             w,
+            shape: QuadShape::Parallelogram,
         }
     }
-    fn is_interior(a: f64, b: f64) -> Option<(f64, f64)> {
-        // Given the hit point in plane coordinates, return false if it is outside the
-        // primitive, otherwise set the hit record UV coordinates and return true.
-
-        if (a < 0.) || (1. < a) || (b < 0.) || (1. < b) {
-            return None;
-        }
-
-        // a,b == u,v
-        Some((a, b))
+    /// A triangle with corners `q`, `q + u` and `q + v`
+    pub fn triangle(
+        q: DVec3,
+        u: DVec3,
+        v: DVec3,
+        material: Material,
+    ) -> Self {
+        Self::new(q, u, v, material)
+            .with_shape(QuadShape::Triangle)
+    }
+    /// An ellipse centered on `center` with semi-axes `u`
+    /// and `v`
+    pub fn ellipse(
+        center: DVec3,
+        u: DVec3,
+        v: DVec3,
+        material: Material,
+    ) -> Self {
+        Self::new(center, u, v, material)
+            .with_shape(QuadShape::Ellipse)
+    }
+    /// Use a different outline inside the quad's plane
+    pub fn with_shape(mut self, shape: QuadShape) -> Self {
+        self.shape = shape;
+        self
     }
 }
 
@@ -81,10 +217,7 @@ impl Hittable for Quad {
         let beta =
             self.w.dot(self.u.cross(planar_hitpt_vector));
 
-        let Some((u, v)) = Quad::is_interior(alpha, beta)
-        else {
-            return None;
-        };
+        let (u, v) = self.shape.interior(alpha, beta)?;
         // Ray hits the 2D shape; set the rest of the hit record and return true.
         // rec.t = t;
         // rec.p = intersection;
@@ -101,8 +234,26 @@ impl Hittable for Quad {
             ray,
             u,
             v,
-        );
+        )
+        .with_tangent(self.u);
 
         Some(rec)
     }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let (min, max) = self.shape.bounds();
+        Some(
+            Aabb::from_points(
+                [
+                    min,
+                    DVec2::new(max.x, min.y),
+                    DVec2::new(min.x, max.y),
+                    max,
+                ]
+                .map(|p| {
+                    self.Q + p.x * self.u + p.y * self.v
+                }),
+            )
+            .pad(0.0001),
+        )
+    }
 }
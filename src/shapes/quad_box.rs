@@ -1,4 +1,5 @@
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     material::Material,
     ray::Ray,
@@ -101,4 +102,7 @@ impl Hittable for QuadBox {
     ) -> Option<HitRecord> {
         self.objects.hit(ray, interval)
     }
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::new(self.a, self.b))
+    }
 }
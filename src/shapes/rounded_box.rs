@@ -1,376 +1,233 @@
-use glam::{DVec2, DVec3, Vec3Swizzles};
+use glam::{DQuat, DVec3, Vec3Swizzles};
 use std::ops::Range;
 
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     material::Material,
     ray::Ray,
 };
 
+use super::a_box::{face_tangent, face_uv};
+
+/// A box with its edges and corners rounded off
 pub struct RoundedBox {
-    pub center: DVec3,
-    pub radius: f64,
-    pub size: DVec3,
-    pub material: Material,
+    center: DVec3,
+    /// half of the extent of the box's flat faces along
+    /// each local axis. The rounding adds `radius` on top.
+    size: DVec3,
+    radius: f64,
+    rotation: DQuat,
+    material: Material,
 }
 
 // Rounded Box:     https://www.shadertoy.com/view/WlSXRW
 
+impl RoundedBox {
+    /// `size` is half of the extent of the flat faces
+    /// along each axis, so the whole box reaches
+    /// `size + radius` out from the center.
+    pub fn new(
+        center: DVec3,
+        size: DVec3,
+        radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            center,
+            size,
+            radius,
+            rotation: DQuat::IDENTITY,
+            material,
+        }
+    }
+    /// Rotate the box by `angle` degrees around an `axis`
+    /// through its center.
+    pub fn with_rotation(
+        mut self,
+        axis: DVec3,
+        angle: f64,
+    ) -> Self {
+        self.rotation = DQuat::from_axis_angle(
+            axis.normalize(),
+            angle.to_radians(),
+        );
+        self
+    }
+    /// Where the line through `ro` along the unit vector
+    /// `rd` enters the box, which may be behind `ro`.
+    fn line_entry(
+        &self,
+        ro: DVec3,
+        rd: DVec3,
+    ) -> Option<f64> {
+        // start the search from outside of the bounding
+        // box, so the intersector always finds the entry
+        let (near, _) =
+            slabs(ro, rd, self.size + self.radius)?;
+        let shift = near - 1.;
+        let t = roundedbox_intersect(
+            ro + shift * rd,
+            rd,
+            self.size,
+            self.radius,
+        )?;
+        Some(shift + t)
+    }
+}
+
 impl Hittable for RoundedBox {
     fn hit(
         &self,
         ray: &Ray,
         interval: Range<f64>,
     ) -> Option<HitRecord> {
-        let ro = ray.origin;
-        let rd = ray.direction;
-        let dist_bound =
-            DVec2::new(interval.start, interval.end);
-        let size = self.size;
-        let rad = self.radius;
-        // normal unhandled
-
-        // bounding box
-        let m: DVec3 = rd.recip();
-        let n: DVec3 = m * ro;
-        let k: DVec3 = m.abs() * (size + rad);
-        let t1: DVec3 = -n - k;
-        let t2: DVec3 = -n + k;
-        let tN: f64 = t1.x.max(t1.y).max(t1.z);
-        let tF: f64 = t2.x.min(t2.y).min(t2.z);
-        if tN > tF || tF < 0.0 {
-            return None;
-        }
-        // let mut t: f64 = tN;
-        let mut t: f64 =
-            if tN >= dist_bound.x && tN <= dist_bound.y {
-                tN
-            } else {
-                if interval.contains(&tF) {
-                    tF
-                } else {
-                    // max_range
-                    // dist_bound.y
-                    1e100
-                }
-            };
-
-        // convert to first octant
-        let pos: DVec3 = ray.at(t);
-        let s: DVec3 = pos.signum();
-        let ros: DVec3 = ro * s;
-        let rds: DVec3 = rd * s;
-        let pos = pos * s;
-
-        // faces
-        let pos = pos - size;
-        let pos = pos.xyz().max(pos.yzx());
-        if pos.x.min(pos.y).min(pos.z) < 0.0 {
-            if interval.contains(&t) {
-                let p = ray.at(t);
-                let normal = p.signum()
-                    * (p.abs() - size.max(DVec3::ZERO))
-                        .normalize();
-                return Some(HitRecord::with_face_normal(
-                    self.material.clone(),
-                    p,
-                    normal,
-                    t,
-                    ray,
-                ));
-            }
-        }
-
-        // some precomputation
-        let oc: DVec3 = ros - size;
-        let dd: DVec3 = rds * rds;
-        let oo: DVec3 = oc * oc;
-        let od: DVec3 = oc * rds;
-        let ra2: f64 = rad * rad;
-
-        t = interval.end;
-
-        // corner
-        {
-            let b: f64 = od.x + od.y + od.z;
-            let c: f64 = oo.x + oo.y + oo.z - ra2;
-            let h: f64 = b * b - c;
-            if h > 0.0 {
-                t = -b - h.sqrt()
-            };
-        }
-
-        // edge X
-        {
-            let a: f64 = dd.y + dd.z;
-            let b: f64 = od.y + od.z;
-            let c: f64 = oo.y + oo.z - ra2;
-            let mut h: f64 = b * b - a * c;
-            if h > 0.0 {
-                h = (-b - h.sqrt()) / a;
-                if h >= dist_bound.x
-                    && h < t
-                    && (ros.x + rds.x * h).abs() < size.x
-                {
-                    t = h
-                };
-            }
-        }
-        // edge Y
-        {
-            let a: f64 = dd.z + dd.x;
-            let b: f64 = od.z + od.x;
-            let c: f64 = oo.z + oo.x - ra2;
-            let mut h: f64 = b * b - a * c;
-            if h > 0.0 {
-                h = (-b - h.sqrt()) / a;
-                if h >= dist_bound.x
-                    && h < t
-                    && (ros.y + rds.y * h).abs() < size.y
-                {
-                    t = h
-                };
-            }
-        }
-        // edge Z
-        {
-            let a: f64 = dd.x + dd.y;
-            let b: f64 = od.x + od.y;
-            let c: f64 = oo.x + oo.y - ra2;
-            let mut h: f64 = b * b - a * c;
-            if h > 0.0 {
-                h = (-b - h.sqrt()) / a;
-                if h >= dist_bound.x
-                    && h < t
-                    && (ros.z + rds.z * h).abs() < size.z
-                {
-                    t = h
-                };
-            }
-        }
-
-        if interval.contains(&t) {
-            let p = ray.at(t);
-            let normal = p.signum()
-                * (p.abs() - size)
-                    .max(DVec3::splat(1e-16))
-                    .normalize();
-            return Some(HitRecord::with_face_normal(
+        // intersect in the box's local space with a unit
+        // direction, then scale t back to the ray's
+        let to_local = self.rotation.inverse();
+        let ro = to_local * (ray.origin - self.center);
+        let length = ray.direction.length();
+        let rd = to_local * ray.direction / length;
+
+        // the box is convex, so the ray crosses it once on
+        // the way in and once on the way out. The way out
+        // is the way in for the reversed line.
+        let entry = self.line_entry(ro, rd)? / length;
+        let exit = -self.line_entry(ro, -rd)? / length;
+        let t = [entry, exit]
+            .into_iter()
+            .find(|t| interval.contains(t))?;
+
+        let local_point = ro + t * length * rd;
+        let local_normal = roundedbox_normal(
+            local_point,
+            self.size,
+            self.radius,
+        );
+        let (u, v) = face_uv(
+            local_point / (self.size + self.radius),
+            local_normal,
+        );
+        Some(
+            HitRecord::with_face_normal(
                 self.material.clone(),
-                p,
-                normal,
+                ray.at(t),
+                self.rotation * local_normal,
                 t,
                 ray,
-            ));
-        } else {
-            return None;
-        };
+                u,
+                v,
+            )
+            .with_tangent(
+                self.rotation * face_tangent(local_normal),
+            ),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent = self.size + self.radius;
+        let local = Aabb::new(-extent, extent);
+        Some(Aabb::from_points(local.corners().map(
+            |corner| self.center + self.rotation * corner,
+        )))
     }
 }
-// impl Hittable for RoundedBox {
-//     fn hit(
-//         &self,
-//         ray: &Ray,
-//         interval: Range<f64>,
-//     ) -> Option<HitRecord> {
-//         // let oc = ray.origin - self.center;
-//         // let a = ray.direction.length_squared();
-//         // let half_b = oc.dot(ray.direction);
-//         // let c =
-//         //     oc.length_squared() - self.radius * self.radius;
-
-//         // let discriminant = half_b * half_b - a * c;
-//         // if discriminant < 0. {
-//         //     return None;
-//         // }
-//         // let sqrtd = discriminant.sqrt();
-
-//         // // Find the nearest root that lies in the acceptable range.
-//         // let mut root = (-half_b - sqrtd) / a;
-//         // if !interval.contains(&root) {
-//         //     root = (-half_b + sqrtd) / a;
-//         //     if !interval.contains(&root) {
-//         //         return None;
-//         //     }
-//         // }
-
-//         // let t = root;
-//         // let point = ray.at(t);
-//         // let outward_normal =
-//         //     (point - self.center) / self.
-
-//         let tm: DVec2 =
-//             intersect(ray.origin, ray.direction);
-
-//         // convert position from world to box space
-//         let bpos: DVec3 = ptransform(box_world_to_obj, pos);
-//         // compute normal in box space
-//         let bnor: DVec3 =
-//             roundedbox_normal(bpos, box_size, box_radius);
-//         // convert normal from box to world space
-//         let nor: DVec3 = ntransform(box_obj_to_world, bnor);
-
-//         let t = roundedbox_intersect(
-//             ray.origin - self.center,
-//             ray.direction - self.center,
-//             self.size,
-//             self.radius,
-//         );
-//         let point: DVec3 = ray.origin + t * ray.direction;
-//         let outward_normal: DVec3 = roundedbox_normal(
-//             point,
-//             self.size,
-//             self.radius,
-//         );
-
-//         let rec = HitRecord::with_face_normal(
-//             self.material.clone(),
-//             point,
-//             outward_normal,
-//             t,
-//             ray,
-//         );
-
-//         Some(rec)
-//     }
-// }
-// // intersect a ray with a rounded box
-// // https://iquilezles.org/articles/intersectors
-// fn roundedbox_intersect(
-//     ro: DVec3,
-//     rd: DVec3,
-//     size: DVec3,
-//     radius: f64,
-// ) -> f64 {
-//     // bounding box
-//     let m: DVec3 = rd.recip();
-//     let n: DVec3 = m * ro;
-//     let k: DVec3 = m.abs() * (size + radius);
-//     let t1: DVec3 = -n - k;
-//     let t2: DVec3 = -n + k;
-//     let tN: f64 = t1.x.max(t1.y).max(t1.z);
-//     let tF: f64 = t2.x.min(t2.y).min(t2.z);
-//     if tN > tF || tF < 0.0 {
-//         return -1.0;
-//     }
-//     let mut t: f64 = tN;
-
-//     // convert to first octant
-//     let pos: DVec3 = ro + t * rd;
-//     let s: DVec3 = pos.signum();
-//     let ro = ro * s;
-//     let rd = rd * s;
-//     let mut pos = pos * s;
-
-//     // faces
-//     pos -= size;
-//     pos = pos.xyz().max(pos.yzx());
-//     if pos.x.min(pos.y).min(pos.z) < 0.0 {
-//         return t;
-//     }
-
-//     // some precomputation
-//     let oc: DVec3 = ro - size;
-//     let dd: DVec3 = rd * rd;
-//     let oo: DVec3 = oc * oc;
-//     let od: DVec3 = oc * rd;
-//     let ra2: f64 = radius * radius;
-
-//     t = 1e20;
 
-//     // corner
-//     {
-//         let b: f64 = od.x + od.y + od.z;
-//         let c: f64 = oo.x + oo.y + oo.z - ra2;
-//         let h: f64 = b * b - c;
-//         if h > 0.0 {
-//             t = -b - h.sqrt();
-//         }
-//     }
-
-//     // edge X
-//     {
-//         let a: f64 = dd.y + dd.z;
-//         let b: f64 = od.y + od.z;
-//         let c: f64 = oo.y + oo.z - ra2;
-//         let mut h: f64 = b * b - a * c;
-//         if h > 0.0 {
-//             h = (-b - h.sqrt()) / a;
-//             if h > 0.0
-//                 && h < t
-//                 && (ro.x + rd.x * h).abs() < size.x
-//             {
-//                 t = h;
-//             }
-//         }
-//     }
-//     // edge Y
-//     {
-//         let a: f64 = dd.z + dd.x;
-//         let b: f64 = od.z + od.x;
-//         let c: f64 = oo.z + oo.x - ra2;
-//         let mut h: f64 = b * b - a * c;
-//         if h > 0.0 {
-//             h = (-b - h.sqrt()) / a;
-//             if h > 0.0
-//                 && h < t
-//                 && (ro.y + rd.y * h).abs() < size.y
-//             {
-//                 t = h;
-//             }
-//         }
-//     }
-//     // edge Z
-//     {
-//         let a: f64 = dd.x + dd.y;
-//         let b: f64 = od.x + od.y;
-//         let c: f64 = oo.x + oo.y - ra2;
-//         let mut h: f64 = b * b - a * c;
-//         if h > 0.0 {
-//             h = (-b - h.sqrt()) / a;
-//             if h > 0.0
-//                 && h < t
-//                 && (ro.z + rd.z * h).abs() < size.z
-//             {
-//                 t = h;
-//             }
-//         }
-//     }
-
-//     if t > 1e19 {
-//         t = -1.0;
-//     }
-
-//     return t;
-// }
+/// Near and far distances at which a line crosses an
+/// origin-centered box with half extents `size`
+fn slabs(
+    ro: DVec3,
+    rd: DVec3,
+    size: DVec3,
+) -> Option<(f64, f64)> {
+    let m: DVec3 =
+        rd.signum() / rd.abs().max(DVec3::splat(1e-12));
+    let n: DVec3 = m * ro;
+    let k: DVec3 = m.abs() * size;
+    let t1: DVec3 = -n - k;
+    let t2: DVec3 = -n + k;
+    let t_near: f64 = t1.x.max(t1.y).max(t1.z);
+    let t_far: f64 = t2.x.min(t2.y).min(t2.z);
+    (t_near <= t_far).then_some((t_near, t_far))
+}
 
-// // normal of a rounded box
-// fn roundedbox_normal(
-//     pos: DVec3,
-//     siz: DVec3,
-//     rad: f64,
-// ) -> DVec3 {
-//     pos.signum() * pos.abs()
-//         - siz.max(DVec3::ZERO).normalize()
-// }
+// intersect a ray with a rounded box
+// https://iquilezles.org/articles/intersectors
+fn roundedbox_intersect(
+    ro: DVec3,
+    rd: DVec3,
+    size: DVec3,
+    radius: f64,
+) -> Option<f64> {
+    // bounding box
+    let (t_near, t_far) = slabs(ro, rd, size + radius)?;
+    if t_far < 0.0 {
+        return None;
+    }
+    let t = t_near;
+
+    // convert to first octant
+    let pos: DVec3 = ro + t * rd;
+    let s: DVec3 = pos.signum();
+    let ro = ro * s;
+    let rd = rd * s;
+    let pos = pos * s;
+
+    // faces
+    let pos = pos - size;
+    let pos = pos.xyz().max(pos.yzx());
+    if pos.x.min(pos.y).min(pos.z) < 0.0 {
+        return Some(t);
+    }
 
-// fn intersect(ray: &Ray) -> DVec2 {
-//     let mut res = DVec2::new(1e20, -1.0);
+    // some precomputation
+    let oc: DVec3 = ro - size;
+    let dd: DVec3 = rd * rd;
+    let oo: DVec3 = oc * oc;
+    let od: DVec3 = oc * rd;
+    let ra2: f64 = radius * radius;
+
+    let mut t: f64 = f64::INFINITY;
+
+    // corner
+    {
+        let b: f64 = od.x + od.y + od.z;
+        let c: f64 = oo.x + oo.y + oo.z - ra2;
+        let h: f64 = b * b - c;
+        if h > 0.0 {
+            t = -b - h.sqrt();
+        }
+    }
 
-//     // rounded box
+    // edges along each axis
+    for (a, b, c, along) in [
+        (dd.y + dd.z, od.y + od.z, oo.y + oo.z, 0),
+        (dd.z + dd.x, od.z + od.x, oo.z + oo.x, 1),
+        (dd.x + dd.y, od.x + od.y, oo.x + oo.y, 2),
+    ] {
+        let h: f64 = b * b - a * (c - ra2);
+        if h > 0.0 {
+            let h = (-b - h.sqrt()) / a;
+            if h > 0.0
+                && h < t
+                && (ro[along] + rd[along] * h).abs()
+                    < size[along]
+            {
+                t = h;
+            }
+        }
+    }
 
-//     // convert ray from world to box space
-//     let rdd: DVec3 =
-//         ntransform(box_world_to_obj, ray.direction);
-//     let roo: DVec3 =
-//         ptransform(box_world_to_obj, ray.origin);
-//     // intersect in box space
-//     let t: f64 = roundedbox_intersect(
-//         roo, rdd, box_size, box_radius,
-//     );
-//     if t > 0.0 && t < res.x {
-//         res = DVec2::new(t, 2.0);
-//     }
+    t.is_finite().then_some(t)
+}
 
-//     return res;
-// }
+// normal of a rounded box
+fn roundedbox_normal(
+    pos: DVec3,
+    size: DVec3,
+    radius: f64,
+) -> DVec3 {
+    pos.signum()
+        * (pos.abs() - size)
+            .max(DVec3::splat(1e-16 * radius))
+            .normalize()
+}
@@ -0,0 +1,336 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::{DVec2, DVec3, Vec3Swizzles};
+use std::{f64::consts::PI, ops::Range, sync::Arc};
+
+/// How close to the surface a march has to get to count
+/// as a hit
+const EPSILON: f64 = 1e-4;
+/// Give up on rays that haven't hit anything after this
+/// many steps, such as rays that graze the surface
+const MAX_STEPS: u32 = 512;
+
+/// A signed distance function: negative inside the
+/// surface, positive outside and zero on it. Build them
+/// from the primitives and combine them with the methods
+/// below.
+#[derive(Clone)]
+pub enum Distance {
+    Sphere {
+        radius: f64,
+    },
+    /// An origin-centered box with half extents `size`
+    Cuboid {
+        size: DVec3,
+    },
+    /// A torus around the y axis
+    Torus {
+        major_radius: f64,
+        minor_radius: f64,
+    },
+    /// Any distance function. Functions that overestimate
+    /// the distance need a smaller
+    /// [step scale](Sdf::with_step_scale).
+    Custom(Arc<dyn Fn(DVec3) -> f64 + Send + Sync>),
+    Translate {
+        offset: DVec3,
+        distance: Box<Distance>,
+    },
+    Union(Box<Distance>, Box<Distance>),
+    /// A union that blends the two shapes together within
+    /// `k` of where they meet
+    SmoothUnion {
+        a: Box<Distance>,
+        b: Box<Distance>,
+        k: f64,
+    },
+    /// The first shape with the second one cut out of it
+    Subtraction(Box<Distance>, Box<Distance>),
+    Intersection(Box<Distance>, Box<Distance>),
+    /// Copies of the shape repeated forever on a grid
+    /// with cells of size `period`
+    Repeat {
+        period: DVec3,
+        distance: Box<Distance>,
+    },
+    /// Twist the shape around the y axis by `rate`
+    /// radians per unit of height
+    Twist {
+        rate: f64,
+        distance: Box<Distance>,
+    },
+    /// Grow the surface outwards by `radius`, rounding off
+    /// edges and corners
+    Round {
+        radius: f64,
+        distance: Box<Distance>,
+    },
+}
+
+impl Distance {
+    pub fn custom(
+        f: impl Fn(DVec3) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Distance::Custom(Arc::new(f))
+    }
+    pub fn translate(self, offset: DVec3) -> Self {
+        Distance::Translate {
+            offset,
+            distance: Box::new(self),
+        }
+    }
+    pub fn union(self, other: Distance) -> Self {
+        Distance::Union(Box::new(self), Box::new(other))
+    }
+    pub fn smooth_union(
+        self,
+        other: Distance,
+        k: f64,
+    ) -> Self {
+        Distance::SmoothUnion {
+            a: Box::new(self),
+            b: Box::new(other),
+            k,
+        }
+    }
+    pub fn subtract(self, other: Distance) -> Self {
+        Distance::Subtraction(
+            Box::new(self),
+            Box::new(other),
+        )
+    }
+    pub fn intersect(self, other: Distance) -> Self {
+        Distance::Intersection(
+            Box::new(self),
+            Box::new(other),
+        )
+    }
+    pub fn repeat(self, period: DVec3) -> Self {
+        Distance::Repeat {
+            period,
+            distance: Box::new(self),
+        }
+    }
+    pub fn twist(self, rate: f64) -> Self {
+        Distance::Twist {
+            rate,
+            distance: Box::new(self),
+        }
+    }
+    pub fn round(self, radius: f64) -> Self {
+        Distance::Round {
+            radius,
+            distance: Box::new(self),
+        }
+    }
+    /// The signed distance from `p` to the surface
+    pub fn distance(&self, p: DVec3) -> f64 {
+        match self {
+            Distance::Sphere { radius } => {
+                p.length() - radius
+            }
+            Distance::Cuboid { size } => {
+                let q = p.abs() - *size;
+                q.max(DVec3::ZERO).length()
+                    + q.x.max(q.y).max(q.z).min(0.)
+            }
+            Distance::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let q = DVec2::new(
+                    p.xz().length() - major_radius,
+                    p.y,
+                );
+                q.length() - minor_radius
+            }
+            Distance::Custom(f) => f(p),
+            Distance::Translate { offset, distance } => {
+                distance.distance(p - *offset)
+            }
+            Distance::Union(a, b) => {
+                a.distance(p).min(b.distance(p))
+            }
+            Distance::SmoothUnion { a, b, k } => {
+                let d1 = a.distance(p);
+                let d2 = b.distance(p);
+                let h = (0.5 + 0.5 * (d2 - d1) / k)
+                    .clamp(0., 1.);
+                d2 + (d1 - d2) * h - k * h * (1. - h)
+            }
+            Distance::Subtraction(a, b) => {
+                a.distance(p).max(-b.distance(p))
+            }
+            Distance::Intersection(a, b) => {
+                a.distance(p).max(b.distance(p))
+            }
+            Distance::Repeat { period, distance } => {
+                distance.distance(
+                    p - *period * (p / *period).round(),
+                )
+            }
+            Distance::Twist { rate, distance } => {
+                let (s, c) = (rate * p.y).sin_cos();
+                distance.distance(DVec3::new(
+                    c * p.x - s * p.z,
+                    p.y,
+                    s * p.x + c * p.z,
+                ))
+            }
+            Distance::Round { radius, distance } => {
+                distance.distance(p) - radius
+            }
+        }
+    }
+    /// The direction the distance grows fastest in,
+    /// estimated with the tetrahedron technique
+    fn normal(&self, p: DVec3) -> DVec3 {
+        let h = EPSILON * 0.5;
+        [
+            DVec3::new(1., -1., -1.),
+            DVec3::new(-1., -1., 1.),
+            DVec3::new(-1., 1., -1.),
+            DVec3::new(1., 1., 1.),
+        ]
+        .into_iter()
+        .map(|k| k * self.distance(p + k * h))
+        .sum::<DVec3>()
+        .normalize_or_zero()
+    }
+}
+
+/// A shape defined by a signed distance function and
+/// rendered by sphere tracing.
+///
+/// An optional bounding box limits tracing to the part of
+/// each ray that passes through it, which matters for
+/// infinite shapes such as repetitions and speeds up
+/// everything else.
+pub struct Sdf {
+    distance: Distance,
+    bounds: Option<Aabb>,
+    step_scale: f64,
+    material: Material,
+}
+
+impl Sdf {
+    pub fn new(
+        distance: Distance,
+        material: Material,
+    ) -> Self {
+        Self {
+            distance,
+            bounds: None,
+            step_scale: 1.,
+            material,
+        }
+    }
+    /// Only trace rays inside of `bounds`. Everything the
+    /// distance function describes outside of it is cut
+    /// off.
+    pub fn with_bounds(mut self, bounds: Aabb) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+    /// Scale each step of the march. Distance functions
+    /// that overestimate, such as twists or many custom
+    /// fractals, need a value below 1 to avoid stepping
+    /// through the surface.
+    pub fn with_step_scale(
+        mut self,
+        step_scale: f64,
+    ) -> Self {
+        self.step_scale = step_scale;
+        self
+    }
+}
+
+impl Hittable for Sdf {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let interval = match &self.bounds {
+            Some(bounds) => bounds.hit(ray, interval)?,
+            None => interval,
+        };
+
+        // march in units of distance, not of t. There is
+        // no marching from infinitely far back, as when
+        // looking for the way into a shape the ray starts
+        // inside of, so unbounded shapes start at the ray's
+        // origin and find the way out instead.
+        let length = ray.direction.length();
+        let direction = ray.direction / length;
+        let end = interval.end * length;
+        let mut s = if interval.start.is_finite() {
+            interval.start * length
+        } else {
+            0.
+        };
+
+        // Rays that start on the surface, like scattered
+        // rays, first have to leave it. Then we know which
+        // side of the surface we are on, and can find the
+        // way out of the shape as well as the way in.
+        let mut side = 0.;
+        let mut steps = 0;
+        while s <= end && steps < MAX_STEPS {
+            steps += 1;
+            let d = self
+                .distance
+                .distance(ray.origin + s * direction);
+            if side == 0. {
+                if d.abs() < EPSILON {
+                    s += EPSILON;
+                    continue;
+                }
+                side = d.signum();
+            }
+            let d = side * d;
+            if d < EPSILON {
+                break;
+            }
+            s += d * self.step_scale;
+        }
+        if side == 0. || s > end || steps >= MAX_STEPS {
+            return None;
+        }
+
+        let t = s / length;
+        let point = ray.at(t);
+        let outward_normal = self.distance.normal(point);
+
+        // project the normal onto a sphere for UVs
+        let u = ((-outward_normal.z)
+            .atan2(outward_normal.x)
+            + PI)
+            / (2. * PI);
+        let v =
+            (-outward_normal.y).clamp(-1., 1.).acos() / PI;
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                point,
+                outward_normal,
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(DVec3::new(
+                outward_normal.z,
+                0.,
+                -outward_normal.x,
+            )),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bounds
+    }
+}
@@ -3,6 +3,7 @@ use std::{f64::consts::PI, ops::Range};
 use glam::DVec3;
 
 use crate::{
+    aabb::Aabb,
     hittable::{HitRecord, Hittable},
     material::Material,
     ray::Ray,
@@ -122,8 +123,25 @@ impl Hittable for Sphere {
             ray,
             u,
             v,
-        );
+        )
+        .with_tangent(DVec3::new(
+            outward_normal.z,
+            0.,
+            -outward_normal.x,
+        ));
 
         Some(rec)
     }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let radius = DVec3::splat(self.radius.abs());
+        let start = self.center(0.);
+        let end = self.center(1.);
+        Some(
+            Aabb::new(start - radius, start + radius)
+                .union(&Aabb::new(
+                    end - radius,
+                    end + radius,
+                )),
+        )
+    }
 }
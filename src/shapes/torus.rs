@@ -0,0 +1,125 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::DVec3;
+use std::{f64::consts::PI, ops::Range};
+
+use super::{
+    frame::{angle_around_axis, disk_extent, Frame},
+    polynomial::solve_quartic,
+};
+
+/// A ring-shaped tube around `axis`. `major_radius` is
+/// the distance from the center to the middle of the tube
+/// and `minor_radius` is the radius of the tube itself.
+///
+/// u goes around the axis and v goes around the tube.
+pub struct Torus {
+    frame: Frame,
+    major_radius: f64,
+    minor_radius: f64,
+    material: Material,
+}
+
+impl Torus {
+    pub fn new(
+        center: DVec3,
+        axis: DVec3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Material,
+    ) -> Self {
+        Self {
+            frame: Frame::new(center, axis),
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        let length = ray.direction.length();
+        let d =
+            self.frame.local_vector(ray.direction) / length;
+        let mut o = self.frame.local_point(ray.origin);
+
+        // Solving from far away loses precision, so start
+        // from where the line meets the bounding sphere.
+        let bound = self.major_radius + self.minor_radius;
+        let b = o.dot(d);
+        let c = o.length_squared() - bound * bound;
+        let h = b * b - c;
+        if h < 0. {
+            return None;
+        }
+        let shift = (-b - h.sqrt()).max(0.);
+        o += shift * d;
+
+        // (|p|² + R² - r²)² = 4R²(x² + z²), expanded in t
+        let r2 = self.major_radius * self.major_radius;
+        let e = o.length_squared() + r2
+            - self.minor_radius * self.minor_radius;
+        let f = o.dot(d);
+        let coefficients = [
+            4. * f,
+            4. * f * f + 2. * e
+                - 4. * r2 * (d.x * d.x + d.z * d.z),
+            4. * f * e - 8. * r2 * (o.x * d.x + o.z * d.z),
+            e * e - 4. * r2 * (o.x * o.x + o.z * o.z),
+        ];
+
+        let t = solve_quartic(coefficients)
+            .into_iter()
+            .flatten()
+            .map(|t| (shift + t) / length)
+            .filter(|t| interval.contains(t))
+            .min_by(f64::total_cmp)?;
+
+        let point = ray.at(t);
+        let local = self.frame.local_point(point);
+        // the closest point on the circle through the
+        // middle of the tube
+        let ring = DVec3::new(local.x, 0., local.z)
+            .normalize_or_zero()
+            * self.major_radius;
+        let local_normal = (local - ring).normalize();
+
+        let u = angle_around_axis(local);
+        let v = (local_normal.y.atan2(
+            local_normal.dot(ring.normalize_or_zero()),
+        ) + PI)
+            / (2. * PI);
+        Some(
+            HitRecord::with_face_normal(
+                self.material.clone(),
+                point,
+                self.frame.world_vector(local_normal),
+                t,
+                ray,
+                u,
+                v,
+            )
+            .with_tangent(
+                self.frame.tangent_around_axis(local),
+            ),
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        let extent =
+            disk_extent(self.frame.axis, self.major_radius)
+                + self.minor_radius;
+        Some(Aabb::new(
+            self.frame.origin - extent,
+            self.frame.origin + extent,
+        ))
+    }
+}
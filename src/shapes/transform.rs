@@ -0,0 +1,152 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable},
+    ray::Ray,
+};
+use glam::{DMat3, DMat4, DVec3};
+use std::{ops::Range, sync::Arc};
+
+use super::Shapes;
+
+/// Places a shape in the world with an arbitrary affine
+/// transform: any mix of translation, rotation about any
+/// axis, non-uniform scale and shear.
+///
+/// The wrapped shape is held in an [`Arc`], so the same
+/// geometry can be instanced many times without copying
+/// it.
+pub struct Transform {
+    object_to_world: DMat4,
+    world_to_object: DMat4,
+    /// inverse transpose of the object to world matrix,
+    /// which keeps normals perpendicular to the surface
+    /// under non-uniform scale and shear
+    normal_to_world: DMat3,
+    object: Arc<Shapes>,
+}
+
+impl Transform {
+    /// Wrap a shape without moving it
+    pub fn new(object: impl Into<Arc<Shapes>>) -> Self {
+        Self::from_matrix(DMat4::IDENTITY, object)
+    }
+    /// Wrap a shape with an object to world matrix
+    pub fn from_matrix(
+        object_to_world: DMat4,
+        object: impl Into<Arc<Shapes>>,
+    ) -> Self {
+        let world_to_object = object_to_world.inverse();
+        Self {
+            object_to_world,
+            world_to_object,
+            normal_to_world: DMat3::from_mat4(
+                world_to_object,
+            )
+            .transpose(),
+            object: object.into(),
+        }
+    }
+    /// Apply `matrix` after the existing transform
+    pub fn with_matrix(self, matrix: DMat4) -> Self {
+        Self::from_matrix(
+            matrix * self.object_to_world,
+            self.object,
+        )
+    }
+    pub fn with_translation(self, offset: DVec3) -> Self {
+        self.with_matrix(DMat4::from_translation(offset))
+    }
+    /// Rotate by `angle` degrees around `axis`, which
+    /// passes through the origin.
+    pub fn with_rotation(
+        self,
+        axis: DVec3,
+        angle: f64,
+    ) -> Self {
+        self.with_matrix(DMat4::from_axis_angle(
+            axis.normalize(),
+            angle.to_radians(),
+        ))
+    }
+    pub fn with_scale(self, scale: DVec3) -> Self {
+        self.with_matrix(DMat4::from_scale(scale))
+    }
+    /// Shear each axis by the other two. `x` holds how
+    /// much x changes with y and z, `y` how much y changes
+    /// with x and z, and `z` how much z changes with x
+    /// and y.
+    pub fn with_shear(
+        self,
+        x: (f64, f64),
+        y: (f64, f64),
+        z: (f64, f64),
+    ) -> Self {
+        self.with_matrix(DMat4::from_mat3(
+            DMat3::from_cols(
+                DVec3::new(1., y.0, z.0),
+                DVec3::new(x.0, 1., z.1),
+                DVec3::new(x.1, y.1, 1.),
+            ),
+        ))
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(
+        &self,
+        ray: &Ray,
+        interval: Range<f64>,
+    ) -> Option<HitRecord> {
+        hit_transformed(
+            &self.object,
+            &self.world_to_object,
+            &self.object_to_world,
+            &self.normal_to_world,
+            ray,
+            interval,
+        )
+    }
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(
+            self.object
+                .bounding_box()?
+                .transform(&self.object_to_world),
+        )
+    }
+}
+
+/// Hit `object` with a ray moved into its object space,
+/// then move the hit record back out to world space.
+pub(super) fn hit_transformed(
+    object: &Shapes,
+    world_to_object: &DMat4,
+    object_to_world: &DMat4,
+    normal_to_world: &DMat3,
+    ray: &Ray,
+    interval: Range<f64>,
+) -> Option<HitRecord> {
+    // The direction is not normalized after the
+    // transform, so t means the same thing in both
+    // spaces.
+    let object_ray = Ray {
+        origin: world_to_object
+            .transform_point3(ray.origin),
+        direction: world_to_object
+            .transform_vector3(ray.direction),
+        time: ray.time,
+    };
+
+    let mut hit_record =
+        object.hit(&object_ray, interval)?;
+
+    hit_record.point =
+        object_to_world.transform_point3(hit_record.point);
+    hit_record.normal =
+        (*normal_to_world * hit_record.normal).normalize();
+    // Tangents transform like directions, and stay
+    // perpendicular to normals transformed this way.
+    let tangent = object_to_world
+        .transform_vector3(hit_record.tangent);
+
+    Some(hit_record.with_tangent(tangent))
+}
@@ -0,0 +1,189 @@
+//! Spectral rendering: wavelength sampling, RGB to
+//! spectrum upsampling and conversion back to RGB through
+//! CIE XYZ.
+
+use glam::{DVec3, DVec4};
+use rand::Rng;
+use std::sync::OnceLock;
+
+/// The shortest wavelength we render, in nanometers
+pub const WAVELENGTH_MIN: f64 = 380.;
+/// The longest wavelength we render, in nanometers
+pub const WAVELENGTH_MAX: f64 = 780.;
+const WAVELENGTH_RANGE: f64 =
+    WAVELENGTH_MAX - WAVELENGTH_MIN;
+
+/// The wavelengths a single camera path carries, chosen
+/// with hero wavelength sampling: a random hero wavelength
+/// and three more spread evenly across the visible range
+/// after it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wavelengths {
+    lambda: DVec4,
+    secondary_terminated: bool,
+}
+
+impl Wavelengths {
+    pub fn sample() -> Self {
+        let hero = rand::thread_rng()
+            .gen_range(WAVELENGTH_MIN..WAVELENGTH_MAX);
+        let lambda =
+            DVec4::from_array([0., 1., 2., 3.].map(|i| {
+                WAVELENGTH_MIN
+                    + (hero - WAVELENGTH_MIN
+                        + i * WAVELENGTH_RANGE / 4.)
+                        .rem_euclid(WAVELENGTH_RANGE)
+            }));
+        Self {
+            lambda,
+            secondary_terminated: false,
+        }
+    }
+    /// The wavelengths in nanometers, hero first
+    pub fn lambda(&self) -> DVec4 {
+        self.lambda
+    }
+    pub fn hero(&self) -> f64 {
+        self.lambda.x
+    }
+    /// Follow only the hero wavelength from here on. Used
+    /// when the path takes a direction that only makes
+    /// sense for one wavelength, like refraction through a
+    /// dispersive material.
+    pub fn terminate_secondary(&mut self) {
+        self.secondary_terminated = true;
+    }
+    pub fn secondary_terminated(&self) -> bool {
+        self.secondary_terminated
+    }
+}
+
+/// A smooth spectrum for an RGB color, sampled at the
+/// given wavelengths.
+///
+/// Each channel contributes a lobe of the spectrum, with
+/// the lobes normalized to sum to 1 at every wavelength.
+/// That keeps white flat and reflectances at or below 1.
+/// The lobes are fitted so that primaries come back out
+/// close to where they started.
+pub fn rgb_to_spectrum(
+    rgb: DVec3,
+    wavelengths: &Wavelengths,
+) -> DVec4 {
+    DVec4::from_array(wavelengths.lambda.to_array().map(
+        |lambda| {
+            let weights = DVec3::new(
+                gaussian(lambda, 645., 30.),
+                gaussian(lambda, 530., 30.),
+                gaussian(lambda, 460., 25.),
+            );
+            rgb.dot(weights)
+                / (weights.x + weights.y + weights.z)
+        },
+    ))
+}
+
+/// Convert radiance at the path's wavelengths into linear
+/// sRGB, white balanced so that a flat spectrum comes out
+/// white.
+pub fn spectrum_to_rgb(
+    values: DVec4,
+    wavelengths: &Wavelengths,
+) -> DVec3 {
+    let count = if wavelengths.secondary_terminated {
+        1
+    } else {
+        4
+    };
+    let xyz = (0..count)
+        .map(|i| values[i] * cie_xyz(wavelengths.lambda[i]))
+        .sum::<DVec3>()
+        * WAVELENGTH_RANGE
+        / count as f64;
+    xyz_to_rgb(xyz) / white_rgb()
+}
+
+/// The linear sRGB color of white light reflected by a
+/// surface with the given reflectance at each wavelength
+/// in nanometers
+pub fn reflectance_to_rgb(
+    reflectance: impl Fn(f64) -> f64,
+) -> DVec3 {
+    // the color matching functions at the middle of each
+    // step, scaled by the step and white balanced
+    const STEP: usize = 10;
+    static TABLE: OnceLock<Vec<(f64, DVec3)>> =
+        OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        (WAVELENGTH_MIN as usize..WAVELENGTH_MAX as usize)
+            .step_by(STEP)
+            .map(|lambda| {
+                let lambda = (lambda + STEP / 2) as f64;
+                (
+                    lambda,
+                    xyz_to_rgb(
+                        cie_xyz(lambda) * STEP as f64,
+                    ) / white_rgb(),
+                )
+            })
+            .collect()
+    });
+    table
+        .iter()
+        .map(|(lambda, rgb)| reflectance(*lambda) * *rgb)
+        .sum()
+}
+
+/// The linear sRGB response of a flat spectrum, which we
+/// divide by to white balance
+fn white_rgb() -> DVec3 {
+    static WHITE: OnceLock<DVec3> = OnceLock::new();
+    *WHITE.get_or_init(|| {
+        let xyz = (WAVELENGTH_MIN as u32
+            ..WAVELENGTH_MAX as u32)
+            .map(|lambda| cie_xyz(lambda as f64 + 0.5))
+            .sum::<DVec3>();
+        xyz_to_rgb(xyz)
+    })
+}
+
+fn xyz_to_rgb(xyz: DVec3) -> DVec3 {
+    DVec3::new(
+        DVec3::new(3.2404542, -1.5371385, -0.4985314)
+            .dot(xyz),
+        DVec3::new(-0.9692660, 1.8760108, 0.0415560)
+            .dot(xyz),
+        DVec3::new(0.0556434, -0.2040259, 1.0572252)
+            .dot(xyz),
+    )
+}
+
+/// The CIE 1931 color matching functions, using the
+/// multi-lobe fit from Wyman, Sloan and Shirley's "Simple
+/// Analytic Approximations to the CIE XYZ Color Matching
+/// Functions"
+fn cie_xyz(lambda: f64) -> DVec3 {
+    let lobe =
+        |mu: f64, sigma_below: f64, sigma_above: f64| {
+            let sigma = if lambda < mu {
+                sigma_below
+            } else {
+                sigma_above
+            };
+            gaussian(lambda, mu, sigma)
+        };
+    DVec3::new(
+        1.056 * lobe(599.8, 37.9, 31.0)
+            + 0.362 * lobe(442.0, 16.0, 26.7)
+            - 0.065 * lobe(501.1, 20.4, 26.2),
+        0.821 * lobe(568.8, 46.9, 40.5)
+            + 0.286 * lobe(530.9, 16.3, 31.1),
+        1.217 * lobe(437.0, 11.8, 36.0)
+            + 0.681 * lobe(459.0, 26.0, 13.8),
+    )
+}
+
+fn gaussian(x: f64, mu: f64, sigma: f64) -> f64 {
+    let t = (x - mu) / sigma;
+    (-0.5 * t * t).exp()
+}
@@ -100,3 +100,9 @@ impl From<DVec3> for Texture {
         Self::SolidColor(value)
     }
 }
+
+impl From<f64> for Texture {
+    fn from(value: f64) -> Self {
+        Self::SolidColor(DVec3::splat(value))
+    }
+}